@@ -0,0 +1,85 @@
+use std::io::Cursor;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ImageError {
+    #[error("failed to decode the png: {0}")]
+    Decoding(#[from] png::DecodingError),
+    #[error("only indexed colour pngs are supported, found {0:?}")]
+    UnsupportedColor(png::ColorType),
+    #[error("palette index {index} at ({x}, {y}) doesn't fit into 16 colours")]
+    IndexOutOfRange { index: u8, x: usize, y: usize },
+}
+
+// how the pixels of an image end up in memory, matching the video modes
+// of the console
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    // a byte per pixel
+    Bitmap,
+    // two pixels to a byte, the left one in the high nibble
+    Packed,
+}
+
+// an image as indices into the console's 16 colour palette. this is what
+// `.INCBIN` of a png is going to include, the assembler doesn't get past
+// scanning the directive yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    // the colours of the png's palette are ignored, only the indices matter
+    pub fn from_png(data: &[u8]) -> Result<Self, ImageError> {
+        let mut decoder = png::Decoder::new(Cursor::new(data));
+        decoder.set_transformations(png::Transformations::IDENTITY);
+
+        let mut reader = decoder.read_info()?;
+        let mut buf = vec![0; reader.output_buffer_size().unwrap_or_default()];
+        let info = reader.next_frame(&mut buf)?;
+
+        if info.color_type != png::ColorType::Indexed {
+            return Err(ImageError::UnsupportedColor(info.color_type));
+        }
+
+        let (width, height) = (info.width as usize, info.height as usize);
+        let depth = info.bit_depth as usize;
+        let mut pixels = Vec::with_capacity(width * height);
+
+        for (y, line) in buf.chunks_exact(info.line_size).take(height).enumerate() {
+            for x in 0..width {
+                let bit = x * depth;
+                let shift = 8 - depth - bit % 8;
+                let index = (line[bit / 8] >> shift) & ((1u16 << depth) - 1) as u8;
+
+                if index > 0xf {
+                    return Err(ImageError::IndexOutOfRange { index, x, y });
+                }
+                pixels.push(index);
+            }
+        }
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    // the bytes to include into the program for the given layout, an odd
+    // pixel at the end of a packed line is padded with colour 0
+    pub fn to_bytes(&self, layout: Layout) -> Vec<u8> {
+        match layout {
+            Layout::Bitmap => self.pixels.clone(),
+            Layout::Packed => self
+                .pixels
+                .chunks(self.width.max(1))
+                .flat_map(|line| line.chunks(2))
+                .map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0))
+                .collect(),
+        }
+    }
+}
@@ -18,9 +18,11 @@ pub enum TokenValue {
 
 #[derive(Debug, Clone, Copy, PartialEq, strum::EnumString)]
 pub enum Directive {
+    BANK,
     DB,
     DW,
     EQU,
+    INCBIN,
     INCLUDE,
     ORG,
 }
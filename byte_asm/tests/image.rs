@@ -0,0 +1,96 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+use byte_asm::image::{Image, ImageError, Layout};
+use png::{BitDepth, ColorType};
+
+fn encode(width: u32, height: u32, color: ColorType, depth: BitDepth, data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(color);
+        encoder.set_depth(depth);
+        if color == ColorType::Indexed {
+            encoder.set_palette(vec![0; 3 << depth as u8]);
+        }
+
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(data).unwrap();
+    }
+    out
+}
+
+fn indexed(width: u32, height: u32, depth: BitDepth, data: &[u8]) -> Result<Image, ImageError> {
+    Image::from_png(&encode(width, height, ColorType::Indexed, depth, data))
+}
+
+#[test]
+fn one_bit() {
+    let image = indexed(8, 2, BitDepth::One, &[0b1010_0001, 0b0111_1110]).unwrap();
+    assert_eq!((image.width, image.height), (8, 2));
+    assert_eq!(image.pixels, [
+        1, 0, 1, 0, 0, 0, 0, 1,
+        0, 1, 1, 1, 1, 1, 1, 0,
+    ]);
+}
+
+#[test]
+fn two_bits() {
+    let image = indexed(4, 2, BitDepth::Two, &[0b00_01_10_11, 0b11_10_01_00]).unwrap();
+    assert_eq!(image.pixels, [0, 1, 2, 3, 3, 2, 1, 0]);
+}
+
+#[test]
+fn four_bits() {
+    let image = indexed(4, 1, BitDepth::Four, &[0x9f, 0x03]).unwrap();
+    assert_eq!(image.pixels, [9, 15, 0, 3]);
+}
+
+#[test]
+fn eight_bits() {
+    let image = indexed(3, 1, BitDepth::Eight, &[0, 7, 15]).unwrap();
+    assert_eq!(image.pixels, [0, 7, 15]);
+}
+
+#[test]
+fn indices_past_16_colours_are_rejected() {
+    let err = indexed(3, 2, BitDepth::Eight, &[0, 1, 2, 3, 16, 5]).unwrap_err();
+    assert!(matches!(err, ImageError::IndexOutOfRange { index: 16, x: 1, y: 1 }));
+}
+
+#[test]
+fn rows_are_padded_to_whole_bytes() {
+    // 10 pixels take up 2 bytes a row at 1 bit, 3 pixels 2 bytes at 4 bits.
+    // whatever is in the padding bits doesn't show up in the image.
+    let image = indexed(10, 2, BitDepth::One, &[0xff, 0b10_111111, 0x00, 0b01_111111]).unwrap();
+    assert_eq!(image.pixels, [
+        1, 1, 1, 1, 1, 1, 1, 1, 1, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+    ]);
+
+    let image = indexed(3, 2, BitDepth::Four, &[0x12, 0x3f, 0x45, 0x6f]).unwrap();
+    assert_eq!(image.pixels, [1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn only_indexed_images_are_supported() {
+    let grey = encode(2, 1, ColorType::Grayscale, BitDepth::Eight, &[0, 1]);
+    assert!(matches!(Image::from_png(&grey), Err(ImageError::UnsupportedColor(ColorType::Grayscale))));
+
+    let rgb = encode(1, 1, ColorType::Rgb, BitDepth::Eight, &[1, 2, 3]);
+    assert!(matches!(Image::from_png(&rgb), Err(ImageError::UnsupportedColor(ColorType::Rgb))));
+
+    assert!(matches!(Image::from_png(b"not a png"), Err(ImageError::Decoding(_))));
+}
+
+#[test]
+fn layouts() {
+    let image = indexed(4, 1, BitDepth::Eight, &[1, 2, 3, 4]).unwrap();
+    assert_eq!(image.to_bytes(Layout::Bitmap), [1, 2, 3, 4]);
+    assert_eq!(image.to_bytes(Layout::Packed), [0x12, 0x34]);
+}
+
+#[test]
+fn packed_rows_with_an_odd_width_end_in_colour_0() {
+    let image = indexed(3, 2, BitDepth::Eight, &[1, 2, 3, 4, 5, 6]).unwrap();
+    assert_eq!(image.to_bytes(Layout::Packed), [0x12, 0x30, 0x45, 0x60]);
+}
@@ -0,0 +1,123 @@
+use byte_core::*;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+const INSTRUCTIONS: u64 = 10_000;
+
+struct Ram(Vec<u8>);
+
+impl bus::Peripheral for Ram {
+    fn peek(&self, addr: u16) -> u8 {
+        self.0[addr as usize]
+    }
+
+    fn write(&mut self, addr: u16, byte: u8) {
+        self.0[addr as usize] = byte;
+    }
+}
+
+// a handful of small devices mapped in front of the memory, which is
+// what the address decoding has to get through on every access
+fn attach_devices(cpu: &mut cpu::CPU) {
+    for i in 0..8 {
+        let lo = 0xf000 + i * 0x10;
+        cpu.bus.attach(lo, lo + 0x0f, Ram(vec![0; 0x10])).unwrap();
+    }
+}
+
+// loop:
+//   LDA $0200,X
+//   STA $0300,X
+//   INX
+//   BNE loop
+//   JMP loop
+fn load_program(cpu: &mut cpu::CPU) {
+    cpu.reg.pc = 0x8000;
+    cpu.load(
+        &[
+            0xbd, 0x00, 0x02, 0x9d, 0x00, 0x03, 0xe8, 0xd0, 0xf7, 0x4c, 0x00, 0x80,
+        ],
+        0x8000,
+    );
+}
+
+fn peripheral_ram() -> cpu::CPU {
+    let mut cpu = cpu::CPU::default();
+    attach_devices(&mut cpu);
+    cpu.bus
+        .attach(0x0000, 0xefff, Ram(vec![0; 0xf000]))
+        .unwrap();
+    load_program(&mut cpu);
+    cpu
+}
+
+fn slice_ram() -> cpu::CPU {
+    let mut cpu = cpu::CPU::default();
+    attach_devices(&mut cpu);
+    cpu.bus.attach_ram(0x0000, 0xefff).unwrap();
+    load_program(&mut cpu);
+    cpu
+}
+
+type Setup = fn() -> cpu::CPU;
+
+const SETUPS: [(&str, Setup); 2] = [("peripheral ram", peripheral_ram), ("slice ram", slice_ram)];
+
+fn bench_cpu(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cpu");
+    group.throughput(Throughput::Elements(INSTRUCTIONS));
+
+    for (name, init) in SETUPS {
+        let mut cpu = init();
+        group.bench_function(name, |b| {
+            b.iter(|| (0..INSTRUCTIONS).for_each(|_| cpu.step().unwrap()))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_bus(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bus");
+    group.throughput(Throughput::Elements(1 << 16));
+
+    for (name, init) in SETUPS {
+        let mut cpu = init();
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                (0..=0xffff).for_each(|addr| {
+                    black_box(cpu.bus.read(addr));
+                })
+            })
+        });
+    }
+
+    group.finish();
+}
+
+// finding the device behind every address, through the decode table and
+// through the linear scan it replaced
+fn bench_decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    group.throughput(Throughput::Elements(1 << 16));
+
+    let cpu = slice_ram();
+    group.bench_function("lookup table", |b| {
+        b.iter(|| {
+            (0..=0xffff).for_each(|addr| {
+                black_box(cpu.bus.get_peripheral_index(addr));
+            })
+        })
+    });
+    group.bench_function("linear scan", |b| {
+        b.iter(|| {
+            (0..=0xffff).for_each(|addr| {
+                black_box(cpu.bus.get_peripheral_index_linear(addr));
+            })
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_cpu, bench_bus, bench_decode);
+criterion_main!(benches);
@@ -0,0 +1,96 @@
+use super::{BusError, Region};
+
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    lo: u16,
+    bank: usize,
+}
+
+// swaps banks of a rom that doesn't fit into the address space in and out
+// of windows on the bus. writing `n` to the i-th select register shows
+// bank `n` in the i-th window, reading it back returns the current bank.
+pub struct Mapper {
+    rom: Box<[u8]>,
+    bank_size: u16,
+    windows: Vec<Window>,
+    select: Option<u16>,
+    priority: u8,
+}
+
+impl Mapper {
+    pub fn new(rom: &[u8], bank_size: u16) -> Result<Self, BusError> {
+        let partial_bank = rom.len() & (bank_size as usize).wrapping_sub(1) != 0;
+        if !bank_size.is_power_of_two() || rom.is_empty() || partial_bank {
+            return Err(BusError::InvalidBanks {
+                bank_size,
+                rom_size: rom.len(),
+            });
+        }
+
+        Ok(Self {
+            rom: rom.into(),
+            bank_size,
+            windows: Vec::new(),
+            select: None,
+            priority: 0,
+        })
+    }
+
+    // adds a window of `bank_size` bytes at `lo`, initially showing `bank`
+    pub fn with_window(mut self, lo: u16, bank: usize) -> Self {
+        let bank = bank % self.bank_count();
+        self.windows.push(Window { lo, bank });
+        self
+    }
+
+    // the select registers start at `addr`, one for every window
+    pub fn with_select(mut self, addr: u16) -> Self {
+        self.select = Some(addr);
+        self
+    }
+
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn bank_size(&self) -> u16 {
+        self.bank_size
+    }
+
+    pub fn bank_count(&self) -> usize {
+        self.rom.len() / self.bank_size as usize
+    }
+
+    pub fn bank(&self, window: usize) -> Option<usize> {
+        self.windows.get(window).map(|window| window.bank)
+    }
+
+    // bank numbers past the end of the rom wrap around
+    pub fn select(&mut self, window: usize, bank: usize) {
+        let count = self.bank_count();
+
+        if let Some(window) = self.windows.get_mut(window) {
+            window.bank = bank % count;
+        }
+    }
+
+    pub(super) fn read(&self, window: usize, offset: u16) -> u8 {
+        let bank = self.windows[window].bank;
+        self.rom[bank * self.bank_size as usize + offset as usize]
+    }
+
+    pub(super) fn window_regions(&self) -> impl Iterator<Item = Region> + '_ {
+        self.windows.iter().map(|window| {
+            Region::new(window.lo, window.lo.wrapping_add(self.bank_size - 1))
+                .with_priority(self.priority)
+        })
+    }
+
+    pub(super) fn select_region(&self) -> Option<Region> {
+        self.select.map(|addr| {
+            let hi = addr.wrapping_add(self.windows.len().max(1) as u16 - 1);
+            Region::new(addr, hi).with_priority(self.priority)
+        })
+    }
+}
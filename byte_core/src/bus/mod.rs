@@ -0,0 +1,564 @@
+mod mapper;
+pub mod timer;
+
+pub use mapper::Mapper;
+pub use timer::Timer;
+
+use std::{cell::RefCell, rc::Rc};
+
+pub trait Peripheral {
+    // reads a byte without any side effects, used by debuggers and monitors
+    fn peek(&self, addr: u16) -> u8;
+
+    // reads a byte on behalf of the cpu. peripherals with read side effects,
+    // like read-to-clear status registers or fifos, override this one
+    fn read(&mut self, addr: u16) -> u8 {
+        self.peek(addr)
+    }
+
+    fn write(&mut self, addr: u16, byte: u8);
+}
+
+// lets a peripheral be attached to the bus while something else, like
+// the code that clocks it, holds on to it as well
+impl<P: Peripheral> Peripheral for Rc<RefCell<P>> {
+    fn peek(&self, addr: u16) -> u8 {
+        self.borrow().peek(addr)
+    }
+
+    fn read(&mut self, addr: u16) -> u8 {
+        self.borrow_mut().read(addr)
+    }
+
+    fn write(&mut self, addr: u16, byte: u8) {
+        self.borrow_mut().write(addr, byte);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusError {
+    InvalidRange {
+        lo: u16,
+        hi: u16,
+    },
+    Overlap {
+        range: (u16, u16),
+        other: (u16, u16),
+    },
+    // mirrors have to be a power of two in size and fit into the range
+    InvalidMirror {
+        range: (u16, u16),
+        size: u16,
+    },
+    // the memory given doesn't match the size the region needs
+    SizeMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    // banks have to be a power of two in size and evenly divide the rom
+    InvalidBanks {
+        bank_size: u16,
+        rom_size: usize,
+    },
+    TooManyDevices,
+    UnknownHandle(Handle),
+    // mappers span several regions and can't be moved as a whole
+    CannotRemap(Handle),
+}
+
+impl core::fmt::Display for BusError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BusError::InvalidRange { lo, hi } => write!(f, "invalid range: [{lo:x}:{hi:x}]"),
+            BusError::Overlap { range, other } => write!(
+                f,
+                "overlapping ranges: [{:x}:{:x}] and [{:x}:{:x}]",
+                range.0, range.1, other.0, other.1
+            ),
+            BusError::InvalidMirror { range, size } => write!(
+                f,
+                "can't mirror {size:#x} bytes across [{:x}:{:x}]",
+                range.0, range.1
+            ),
+            BusError::SizeMismatch { expected, actual } => {
+                write!(f, "expected {expected:#x} bytes, got {actual:#x}")
+            }
+            BusError::InvalidBanks {
+                bank_size,
+                rom_size,
+            } => write!(
+                f,
+                "can't split {rom_size:#x} bytes into banks of {bank_size:#x}"
+            ),
+            BusError::TooManyDevices => write!(f, "too many devices attached"),
+            BusError::UnknownHandle(handle) => write!(f, "unknown handle: {handle:?}"),
+            BusError::CannotRemap(handle) => write!(f, "can't remap {handle:?}"),
+        }
+    }
+}
+
+impl std::error::Error for BusError {}
+
+// identifies an attached device, handles are never reused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle(u32);
+
+// where and how a device shows up on the bus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub lo: u16,
+    pub hi: u16,
+    // the size of the device if it's mirrored across the range
+    pub mirror: Option<u16>,
+    // overlapping regions are allowed as long as their priorities differ,
+    // the one with the higher priority shadows the other
+    pub priority: u8,
+}
+
+impl Region {
+    pub fn new(lo: u16, hi: u16) -> Self {
+        Self {
+            lo,
+            hi,
+            mirror: None,
+            priority: 0,
+        }
+    }
+
+    pub fn mirrored(mut self, size: u16) -> Self {
+        self.mirror = Some(size);
+        self
+    }
+
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    // the number of bytes the device behind this region has to provide
+    pub fn device_size(&self) -> usize {
+        match self.mirror {
+            Some(size) => size as usize,
+            None => self.size(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        (self.hi as usize + 1).saturating_sub(self.lo as usize)
+    }
+
+    fn validate(&self) -> Result<u16, BusError> {
+        let range = (self.lo, self.hi);
+        if self.lo > self.hi {
+            return Err(BusError::InvalidRange {
+                lo: self.lo,
+                hi: self.hi,
+            });
+        }
+
+        match self.mirror {
+            None => Ok(u16::MAX),
+            Some(size) if size.is_power_of_two() && size as usize <= self.size() => Ok(size - 1),
+            Some(size) => Err(BusError::InvalidMirror { range, size }),
+        }
+    }
+
+    fn overlaps(&self, other: &Region) -> bool {
+        self.lo <= other.hi && other.lo <= self.hi
+    }
+}
+
+// what sits behind a mapped range. plain memory is kept on the bus itself
+// so that ram/rom accesses don't have to go through a trait object
+enum Device {
+    Peripheral(Box<dyn Peripheral>),
+    Memory { data: Box<[u8]>, writable: bool },
+    // a window or the select registers of the mapper with the same handle
+    Window(usize),
+    Select,
+}
+
+struct Mapping {
+    handle: Handle,
+    region: Region,
+    // applied to the offset into the region, for mirroring
+    mask: u16,
+    device: Device,
+}
+
+// marks an address with nothing attached to it in the decode table
+const UNMAPPED: u8 = u8::MAX;
+
+pub struct Bus {
+    mappings: Vec<Mapping>,
+    mappers: Vec<(Handle, Mapper)>,
+    // index into `mappings` for every address, rebuilt whenever the
+    // mappings change
+    decode: Box<[u8; 1 << 16]>,
+    next_handle: u32,
+    fault: Option<u16>,
+}
+
+impl Default for Bus {
+    fn default() -> Self {
+        Self {
+            mappings: Vec::new(),
+            mappers: Vec::new(),
+            decode: Box::new([UNMAPPED; 1 << 16]),
+            next_handle: 0,
+            fault: None,
+        }
+    }
+}
+
+impl Bus {
+    #[inline]
+    pub fn read(&mut self, addr: u16) -> u8 {
+        match self.get_peripheral_index(addr) {
+            Some((i, addr)) => match &mut self.mappings[i].device {
+                Device::Peripheral(peripheral) => peripheral.read(addr),
+                _ => self.peek_device(i, addr),
+            },
+            None => 0,
+        }
+    }
+
+    #[inline]
+    pub fn peek(&self, addr: u16) -> u8 {
+        match self.get_peripheral_index(addr) {
+            Some((i, addr)) => self.peek_device(i, addr),
+            None => 0,
+        }
+    }
+
+    #[inline]
+    pub fn write(&mut self, addr: u16, byte: u8) {
+        if !self.store(addr, byte, false) {
+            self.fault = Some(addr);
+        }
+    }
+
+    // writes a byte even if it's in rom, meant for program loaders and
+    // debuggers. mapper windows are still left alone.
+    pub fn poke(&mut self, addr: u16, byte: u8) {
+        self.store(addr, byte, true);
+    }
+
+    // the address of the last write that hit read-only memory, cleared
+    // by reading it
+    pub fn take_fault(&mut self) -> Option<u16> {
+        self.fault.take()
+    }
+
+    // returns false if the write hit read-only memory
+    #[inline]
+    fn store(&mut self, addr: u16, byte: u8, force: bool) -> bool {
+        let Some((i, addr)) = self.get_peripheral_index(addr) else {
+            return true;
+        };
+        let mapping = &mut self.mappings[i];
+
+        match &mut mapping.device {
+            Device::Peripheral(peripheral) => peripheral.write(addr, byte),
+            Device::Memory { data, writable } => {
+                if !(*writable || force) {
+                    return false;
+                }
+                data[addr as usize] = byte;
+            }
+            Device::Select => {
+                if let Some(mapper) = find_mapper(&mut self.mappers, mapping.handle) {
+                    mapper.select(addr as usize, byte as usize);
+                }
+            }
+            Device::Window(_) => return false,
+        }
+
+        true
+    }
+
+    #[inline]
+    fn peek_device(&self, i: usize, addr: u16) -> u8 {
+        let mapping = &self.mappings[i];
+        let mapper = || self.mapper(mapping.handle);
+
+        match &mapping.device {
+            Device::Peripheral(peripheral) => peripheral.peek(addr),
+            Device::Memory { data, .. } => data[addr as usize],
+            Device::Window(window) => mapper().map_or(0, |mapper| mapper.read(*window, addr)),
+            Device::Select => mapper()
+                .and_then(|mapper| mapper.bank(addr as usize))
+                .map_or(0, |bank| bank as u8),
+        }
+    }
+
+    // 16-bit accesses are two byte accesses, each routed on its own, so a
+    // word may straddle two peripherals or wrap around from $ffff to $0000
+    pub fn read_u16(&mut self, addr: u16) -> u16 {
+        let lo = self.read(addr);
+        let hi = self.read(addr.wrapping_add(1));
+
+        u16::from_le_bytes([lo, hi])
+    }
+
+    pub fn peek_u16(&self, addr: u16) -> u16 {
+        u16::from_le_bytes([self.peek(addr), self.peek(addr.wrapping_add(1))])
+    }
+
+    pub fn write_u16(&mut self, addr: u16, data: u16) {
+        let [lo, hi] = data.to_le_bytes();
+
+        self.write(addr, lo);
+        self.write(addr.wrapping_add(1), hi);
+    }
+
+    #[inline]
+    pub fn get_peripheral_index(&self, addr: u16) -> Option<(usize, u16)> {
+        match self.decode[addr as usize] {
+            UNMAPPED => None,
+            i => {
+                let mapping = &self.mappings[i as usize];
+                Some((i as usize, (addr - mapping.region.lo) & mapping.mask))
+            }
+        }
+    }
+
+    // the scan over the mappings that the decode table replaced, kept
+    // around to benchmark the table against
+    #[doc(hidden)]
+    pub fn get_peripheral_index_linear(&self, addr: u16) -> Option<(usize, u16)> {
+        self.mappings
+            .iter()
+            .enumerate()
+            .filter(|(_, mapping)| mapping.region.lo <= addr && addr <= mapping.region.hi)
+            .max_by_key(|(_, mapping)| mapping.region.priority)
+            .map(|(i, mapping)| (i, (addr - mapping.region.lo) & mapping.mask))
+    }
+
+    pub fn attach<P>(&mut self, lo: u16, hi: u16, peripheral: P) -> Result<Handle, BusError>
+    where
+        P: Peripheral + 'static,
+    {
+        self.attach_region(Region::new(lo, hi), peripheral)
+    }
+
+    pub fn attach_region<P>(&mut self, region: Region, peripheral: P) -> Result<Handle, BusError>
+    where
+        P: Peripheral + 'static,
+    {
+        self.map(region, Device::Peripheral(Box::new(peripheral)))
+    }
+
+    // zero initialized memory, served directly by the bus
+    pub fn attach_ram(&mut self, lo: u16, hi: u16) -> Result<Handle, BusError> {
+        self.attach_ram_region(Region::new(lo, hi))
+    }
+
+    pub fn attach_ram_region(&mut self, region: Region) -> Result<Handle, BusError> {
+        let data = vec![0; region.device_size()];
+        self.map(
+            region,
+            Device::Memory {
+                data: data.into(),
+                writable: true,
+            },
+        )
+    }
+
+    // read-only memory starting at `lo`, writes to it are ignored
+    pub fn attach_rom(&mut self, lo: u16, data: &[u8]) -> Result<Handle, BusError> {
+        let hi = (lo as usize + data.len()).wrapping_sub(1);
+        let hi = match u16::try_from(hi) {
+            Ok(hi) if !data.is_empty() => hi,
+            _ => return Err(BusError::InvalidRange { lo, hi: hi as u16 }),
+        };
+
+        self.attach_rom_region(Region::new(lo, hi), data)
+    }
+
+    pub fn attach_rom_region(&mut self, region: Region, data: &[u8]) -> Result<Handle, BusError> {
+        if data.len() != region.device_size() {
+            return Err(BusError::SizeMismatch {
+                expected: region.device_size(),
+                actual: data.len(),
+            });
+        }
+
+        self.map(
+            region,
+            Device::Memory {
+                data: data.into(),
+                writable: false,
+            },
+        )
+    }
+
+    // maps the windows and select registers of `mapper`, they all share
+    // the returned handle
+    pub fn attach_mapper(&mut self, mapper: Mapper) -> Result<Handle, BusError> {
+        let mut regions: Vec<(Region, Device)> = mapper
+            .window_regions()
+            .enumerate()
+            .map(|(window, region)| (region, Device::Window(window)))
+            .collect();
+        if let Some(region) = mapper.select_region() {
+            regions.push((region, Device::Select));
+        }
+
+        let mut masks = Vec::new();
+        for (i, (region, _)) in regions.iter().enumerate() {
+            masks.push(self.check(region, None)?);
+
+            if let Some((other, _)) = regions[..i]
+                .iter()
+                .find(|(other, _)| other.priority == region.priority && other.overlaps(region))
+            {
+                return Err(BusError::Overlap {
+                    range: (region.lo, region.hi),
+                    other: (other.lo, other.hi),
+                });
+            }
+        }
+        if self.mappings.len() + regions.len() > UNMAPPED as usize {
+            return Err(BusError::TooManyDevices);
+        }
+
+        let handle = self.next_handle();
+        for ((region, device), mask) in regions.into_iter().zip(masks) {
+            self.mappings.push(Mapping {
+                handle,
+                region,
+                mask,
+                device,
+            });
+        }
+        self.mappers.push((handle, mapper));
+        self.rebuild();
+        Ok(handle)
+    }
+
+    pub fn mapper(&self, handle: Handle) -> Option<&Mapper> {
+        self.mappers
+            .iter()
+            .find(|(other, _)| *other == handle)
+            .map(|(_, mapper)| mapper)
+    }
+
+    pub fn mapper_mut(&mut self, handle: Handle) -> Option<&mut Mapper> {
+        find_mapper(&mut self.mappers, handle)
+    }
+
+    pub fn detach(&mut self, handle: Handle) -> Result<(), BusError> {
+        self.position(handle)?;
+
+        self.mappings.retain(|mapping| mapping.handle != handle);
+        self.mappers.retain(|(other, _)| *other != handle);
+        self.rebuild();
+        Ok(())
+    }
+
+    // moves an attached device to a different region
+    pub fn remap(&mut self, handle: Handle, region: Region) -> Result<(), BusError> {
+        let index = self.position(handle)?;
+        if self.mapper(handle).is_some() {
+            return Err(BusError::CannotRemap(handle));
+        }
+        let mask = self.check(&region, Some(handle))?;
+
+        if let Device::Memory { data, .. } = &self.mappings[index].device {
+            if data.len() != region.device_size() {
+                return Err(BusError::SizeMismatch {
+                    expected: region.device_size(),
+                    actual: data.len(),
+                });
+            }
+        }
+
+        let mapping = &mut self.mappings[index];
+        mapping.region = region;
+        mapping.mask = mask;
+        self.rebuild();
+        Ok(())
+    }
+
+    pub fn region(&self, handle: Handle) -> Option<Region> {
+        self.position(handle)
+            .ok()
+            .map(|index| self.mappings[index].region)
+    }
+
+    fn position(&self, handle: Handle) -> Result<usize, BusError> {
+        self.mappings
+            .iter()
+            .position(|mapping| mapping.handle == handle)
+            .ok_or(BusError::UnknownHandle(handle))
+    }
+
+    // validates `region` against the existing mappings, except for `ignore`
+    fn check(&self, region: &Region, ignore: Option<Handle>) -> Result<u16, BusError> {
+        let mask = region.validate()?;
+
+        for mapping in self.mappings.iter() {
+            if Some(mapping.handle) != ignore
+                && mapping.region.priority == region.priority
+                && mapping.region.overlaps(region)
+            {
+                return Err(BusError::Overlap {
+                    range: (region.lo, region.hi),
+                    other: (mapping.region.lo, mapping.region.hi),
+                });
+            }
+        }
+
+        Ok(mask)
+    }
+
+    fn map(&mut self, region: Region, device: Device) -> Result<Handle, BusError> {
+        let mask = self.check(&region, None)?;
+        if self.mappings.len() >= UNMAPPED as usize {
+            return Err(BusError::TooManyDevices);
+        }
+
+        let handle = self.next_handle();
+        self.mappings.push(Mapping {
+            handle,
+            region,
+            mask,
+            device,
+        });
+        self.rebuild();
+        Ok(handle)
+    }
+
+    fn next_handle(&mut self) -> Handle {
+        self.next_handle += 1;
+        Handle(self.next_handle - 1)
+    }
+
+    // fills the decode table from the lowest priority up, so that
+    // higher priority mappings overwrite what they shadow
+    fn rebuild(&mut self) {
+        let mut order: Vec<usize> = (0..self.mappings.len()).collect();
+        order.sort_by_key(|&i| self.mappings[i].region.priority);
+
+        self.decode.fill(UNMAPPED);
+        for i in order {
+            let region = &self.mappings[i].region;
+            self.decode[region.lo as usize..=region.hi as usize].fill(i as u8);
+        }
+    }
+
+    // the bytes in `[lo:hi]` as the cpu would read them, without
+    // triggering any read side effects
+    pub fn get_memory_region(&self, (lo, hi): (u16, u16)) -> Vec<u8> {
+        (lo..=hi).map(|addr| self.peek(addr)).collect()
+    }
+}
+
+// a free function so that it can be used while a mapping is borrowed
+fn find_mapper(mappers: &mut [(Handle, Mapper)], handle: Handle) -> Option<&mut Mapper> {
+    mappers
+        .iter_mut()
+        .find(|(other, _)| *other == handle)
+        .map(|(_, mapper)| mapper)
+}
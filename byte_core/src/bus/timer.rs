@@ -0,0 +1,169 @@
+use super::Peripheral;
+
+// register offsets, laid out like the ones of the 6522 via. the i/o port
+// registers in between read as zero and ignore writes.
+pub const T1C_L: u16 = 0x4;
+pub const T1C_H: u16 = 0x5;
+pub const T1L_L: u16 = 0x6;
+pub const T1L_H: u16 = 0x7;
+pub const T2C_L: u16 = 0x8;
+pub const T2C_H: u16 = 0x9;
+pub const ACR: u16 = 0xb;
+pub const IFR: u16 = 0xd;
+pub const IER: u16 = 0xe;
+pub const REGISTER_COUNT: u16 = 16;
+
+// interrupt flags, the same bits are used by `IFR` and `IER`
+pub const IRQ_T1: u8 = 0b0100_0000;
+pub const IRQ_T2: u8 = 0b0010_0000;
+// set in `IFR` while any enabled flag is, and in `IER` on every read
+const IRQ_ANY: u8 = 0b1000_0000;
+
+// the auxiliary control register bit that keeps timer 1 reloading itself
+const ACR_FREE_RUNNING: u8 = 0b0100_0000;
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Counter {
+    value: u16,
+    latch: u16,
+    // one-shot timers only interrupt once per write to the counter
+    armed: bool,
+    // a free-running counter shows $ffff for a cycle before it reloads
+    reload: bool,
+}
+
+impl Counter {
+    fn start(&mut self) {
+        self.value = self.latch;
+        self.armed = true;
+        self.reload = false;
+    }
+
+    // counts `cycles` down, returns whether the counter fired
+    fn clock(&mut self, mut cycles: u64, free_running: bool) -> bool {
+        let mut fired = false;
+
+        while cycles > 0 {
+            if self.reload {
+                self.value = self.latch;
+                self.reload = false;
+                cycles -= 1;
+                continue;
+            }
+
+            let steps = cycles.min(self.value as u64 + 1);
+            let underflow = steps == self.value as u64 + 1;
+            self.value = self.value.wrapping_sub(steps as u16);
+            cycles -= steps;
+
+            if !underflow {
+                continue;
+            }
+            if free_running {
+                fired = true;
+                self.reload = true;
+            } else if self.armed {
+                fired = true;
+                self.armed = false;
+            }
+        }
+
+        fired
+    }
+}
+
+// the two 16-bit timers of a 6522 via. both count down once per cycle
+// and set their interrupt flag when they pass zero. timer 1 either fires
+// once or, in free-running mode, reloads from its latch every `n + 2`
+// cycles. timer 2 is always one-shot.
+//
+// the timer doesn't see the cpu's cycles by itself, whoever owns it has
+// to `clock` it and check `irq` after every instruction. sharing it
+// through an `Rc<RefCell<Timer>>` allows for both while it's attached.
+#[derive(Debug, Default, Clone)]
+pub struct Timer {
+    t1: Counter,
+    t2: Counter,
+    acr: u8,
+    ifr: u8,
+    ier: u8,
+}
+
+impl Timer {
+    pub fn clock(&mut self, cycles: u64) {
+        if self.t1.clock(cycles, self.acr & ACR_FREE_RUNNING != 0) {
+            self.ifr |= IRQ_T1;
+        }
+        if self.t2.clock(cycles, false) {
+            self.ifr |= IRQ_T2;
+        }
+    }
+
+    // whether the timer is asserting its interrupt line, which it keeps
+    // doing until the flag is cleared or the interrupt disabled
+    pub fn irq(&self) -> bool {
+        self.ifr & self.ier & (IRQ_T1 | IRQ_T2) != 0
+    }
+}
+
+impl Peripheral for Timer {
+    fn peek(&self, addr: u16) -> u8 {
+        match addr % REGISTER_COUNT {
+            T1C_L => self.t1.value.to_le_bytes()[0],
+            T1C_H => self.t1.value.to_le_bytes()[1],
+            T1L_L => self.t1.latch.to_le_bytes()[0],
+            T1L_H => self.t1.latch.to_le_bytes()[1],
+            T2C_L => self.t2.value.to_le_bytes()[0],
+            T2C_H => self.t2.value.to_le_bytes()[1],
+            ACR => self.acr,
+            IFR if self.irq() => self.ifr | IRQ_ANY,
+            IFR => self.ifr,
+            IER => self.ier | IRQ_ANY,
+            _ => 0,
+        }
+    }
+
+    // reading the low byte of a counter acknowledges its interrupt
+    fn read(&mut self, addr: u16) -> u8 {
+        let byte = self.peek(addr);
+
+        match addr % REGISTER_COUNT {
+            T1C_L => self.ifr &= !IRQ_T1,
+            T2C_L => self.ifr &= !IRQ_T2,
+            _ => (),
+        }
+
+        byte
+    }
+
+    fn write(&mut self, addr: u16, byte: u8) {
+        let set_lo = |value: &mut u16| *value = *value & 0xff00 | byte as u16;
+        let set_hi = |value: &mut u16| *value = *value & 0x00ff | (byte as u16) << 8;
+
+        match addr % REGISTER_COUNT {
+            T1C_L | T1L_L => set_lo(&mut self.t1.latch),
+            T1C_H => {
+                set_hi(&mut self.t1.latch);
+                self.t1.start();
+                self.ifr &= !IRQ_T1;
+            }
+            T1L_H => {
+                set_hi(&mut self.t1.latch);
+                self.ifr &= !IRQ_T1;
+            }
+            T2C_L => set_lo(&mut self.t2.latch),
+            T2C_H => {
+                set_hi(&mut self.t2.latch);
+                self.t2.start();
+                self.ifr &= !IRQ_T2;
+            }
+            ACR => self.acr = byte,
+            // writing ones clears the flags
+            IFR => self.ifr &= !byte,
+            // bit 7 decides whether the other bits get set or cleared
+            IER if byte & IRQ_ANY != 0 => self.ier |= byte & (IRQ_T1 | IRQ_T2),
+            IER => self.ier &= !byte,
+            _ => (),
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use crate::bus::Bus;
+use crate::debugger::{Access, Debugger};
+use crate::hook::{Hook, StepInfo};
 use crate::Error;
 
 use bitflags::bitflags;
@@ -15,7 +17,7 @@ pub enum Operand {
     Address(u16),
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Interrupt {
     IRQ,
     NMI,
@@ -53,6 +55,8 @@ pub struct CPU {
     pub bus: Bus,
     pub cycle: u64,
     pub reg: Registers,
+    pub hook: Option<Box<dyn Hook>>,
+    pub debugger: Debugger,
 }
 
 impl CPU {
@@ -60,7 +64,7 @@ impl CPU {
         program
             .iter()
             .enumerate()
-            .for_each(|(i, b)| self.bus.write(start + i as u16, *b));
+            .for_each(|(i, b)| self.bus.poke(start.wrapping_add(i as u16), *b));
     }
 
     pub fn interrupt(&mut self, interrupt: Interrupt) {
@@ -81,22 +85,56 @@ impl CPU {
             self.set_flag(Flags::INTERRUPT, true);
         }
 
-        self.reg.pc = self.bus.read_u16(vector);
+        self.reg.pc = self.read_u16(vector);
         self.cycle += 7;
+
+        self.debugger.on_interrupt(interrupt, pc, &self.reg);
     }
 
-    // attrs on expressions is still experimental
-    // move this to the line where we match on `opcode.code`
-    #[rustfmt::skip]
     pub fn step(&mut self) -> Result<(), Error> {
-        let opcode = self.bus.read(self.reg.pc);
+        let pc = self.reg.pc;
+        let code = self.fetch(pc);
         self.reg.pc = self.reg.pc.wrapping_add(1);
-        let pc_copy = self.reg.pc;
 
         let opcode = OPCODE_MAP
-            .get(opcode as usize)
+            .get(code as usize)
             .and_then(|opcode| opcode.as_ref())
-            .ok_or(Error::UnrecognizedOpcode(opcode))?;
+            .ok_or(Error::UnrecognizedOpcode(code))?;
+
+        // keep the common path free of any hook related work
+        if self.hook.is_none() {
+            self.execute(opcode);
+            return Ok(());
+        }
+
+        let mut info = StepInfo {
+            pc,
+            opcode: *opcode,
+            operand: self.operand_address(opcode),
+            reg: self.reg,
+            cycle: self.cycle,
+        };
+        info.reg.pc = pc;
+
+        if let Some(hook) = self.hook.as_mut() {
+            hook.before_instruction(&info, &self.bus);
+        }
+        self.execute(opcode);
+
+        info.reg = self.reg;
+        info.cycle = self.cycle;
+        if let Some(hook) = self.hook.as_mut() {
+            hook.after_instruction(&info, &self.bus);
+        }
+
+        Ok(())
+    }
+
+    // attrs on expressions is still experimental
+    // move this to the line where we match on `opcode.code`
+    #[rustfmt::skip]
+    fn execute(&mut self, opcode: &Opcode) {
+        let pc_copy = self.reg.pc;
 
         match opcode.code {
             0x69 | 0x65 | 0x75 | 0x6d | 0x7d | 0x79 | 0x61 | 0x71 => self.adc(opcode),
@@ -163,7 +201,7 @@ impl CPU {
 
             0x00 => {
                 self.interrupt(Interrupt::BRK);
-                return Ok(());
+                return;
             }
             0x20 => self.jsr(opcode),
             0xea => {},
@@ -175,12 +213,69 @@ impl CPU {
         }
 
         self.cycle += opcode.tick as u64;
-        Ok(())
+    }
+
+    fn read(&mut self, addr: u16) -> u8 {
+        let byte = self.bus.read(addr);
+
+        if let Some(hook) = self.hook.as_mut() {
+            hook.on_read(addr, byte);
+        }
+        self.debugger.on_access(addr, Access::READ, &self.reg);
+        byte
+    }
+
+    // reads a byte of the instruction being executed. these only count as
+    // executing the code, so read watchpoints don't see them.
+    fn fetch(&mut self, addr: u16) -> u8 {
+        let byte = self.bus.read(addr);
+
+        if let Some(hook) = self.hook.as_mut() {
+            hook.on_read(addr, byte);
+        }
+        byte
+    }
+
+    fn fetch_u16(&mut self, addr: u16) -> u16 {
+        let lo = self.fetch(addr);
+        let hi = self.fetch(addr.wrapping_add(1));
+
+        u16::from_le_bytes([lo, hi])
+    }
+
+    // immediate operands and branch offsets are part of the instruction
+    fn read_operand(&mut self, opcode: &Opcode, addr: u16) -> u8 {
+        match opcode.mode {
+            AddressingMode::Immediate | AddressingMode::Relative => self.fetch(addr),
+            _ => self.read(addr),
+        }
+    }
+
+    fn read_u16(&mut self, addr: u16) -> u16 {
+        let lo = self.read(addr);
+        let hi = self.read(addr.wrapping_add(1));
+
+        u16::from_le_bytes([lo, hi])
+    }
+
+    // pointers in the zero page wrap around to $00 instead of crossing into $0100
+    fn read_zp_u16(&mut self, ptr: u8) -> u16 {
+        let lo = self.read(ptr as u16);
+        let hi = self.read(ptr.wrapping_add(1) as u16);
+
+        u16::from_le_bytes([lo, hi])
+    }
+
+    fn write(&mut self, addr: u16, byte: u8) {
+        if let Some(hook) = self.hook.as_mut() {
+            hook.on_write(addr, byte);
+        }
+        self.debugger.on_access(addr, Access::WRITE, &self.reg);
+        self.bus.write(addr, byte);
     }
 
     pub fn stack_push(&mut self, byte: u8) {
-        self.bus
-            .write(STACK_BASE.wrapping_add(self.reg.sp as u16), byte);
+        self.write(STACK_BASE.wrapping_add(self.reg.sp as u16), byte);
         self.reg.sp = self.reg.sp.wrapping_sub(1);
     }
 
@@ -191,7 +286,7 @@ impl CPU {
 
     pub fn stack_pull(&mut self) -> u8 {
         self.reg.sp = self.reg.sp.wrapping_add(1);
-        self.bus.read(STACK_BASE.wrapping_add(self.reg.sp as u16))
+        self.read(STACK_BASE.wrapping_add(self.reg.sp as u16))
     }
 
     pub fn stack_pull_u16(&mut self) -> u16 {
@@ -222,70 +317,105 @@ impl CPU {
         Operand::Address(addr)
     }
 
+    // computes the effective address the same way `get_operand` does, but
+    // without touching the cycle counter or notifying the hook
+    fn operand_address(&self, opcode: &Opcode) -> Option<u16> {
+        let pc = self.reg.pc;
+        let read_u16 = |addr: u16| self.bus.peek_u16(addr);
+        let zp_ptr = |ptr: u8| {
+            let lo = self.bus.peek(ptr as u16) as u16;
+            let hi = self.bus.peek(ptr.wrapping_add(1) as u16) as u16;
+            (hi << 8) | lo
+        };
+
+        let addr = match opcode.mode {
+            AddressingMode::Implied | AddressingMode::Accumulator => return None,
+            AddressingMode::Immediate => pc,
+            AddressingMode::Relative => pc
+                .wrapping_add(1)
+                .wrapping_add(self.bus.peek(pc) as i8 as u16),
+
+            AddressingMode::ZeroPage => self.bus.peek(pc) as u16,
+            AddressingMode::ZeroPageX => self.bus.peek(pc).wrapping_add(self.reg.x) as u16,
+            AddressingMode::ZeroPageY => self.bus.peek(pc).wrapping_add(self.reg.y) as u16,
+
+            AddressingMode::Absolute => read_u16(pc),
+            AddressingMode::AbsoluteX => read_u16(pc).wrapping_add(self.reg.x as u16),
+            AddressingMode::AbsoluteY => read_u16(pc).wrapping_add(self.reg.y as u16),
+
+            AddressingMode::Indirect => {
+                let ptr = read_u16(pc);
+                let lo = self.bus.peek(ptr) as u16;
+                // 6502 indirect jump bug
+                let hi = self
+                    .bus
+                    .peek(ptr & 0xff00 | (ptr as u8).wrapping_add(1) as u16)
+                    as u16;
+                (hi << 8) | lo
+            }
+            AddressingMode::IndirectX => zp_ptr(self.bus.peek(pc).wrapping_add(self.reg.x)),
+            AddressingMode::IndirectY => zp_ptr(self.bus.peek(pc)).wrapping_add(self.reg.y as u16),
+        };
+
+        Some(addr)
+    }
+
     fn get_operand(&mut self, opcode: &Opcode) -> Operand {
         match opcode.mode {
             AddressingMode::Relative => Operand::Address(self.reg.pc),
             AddressingMode::Immediate => Operand::Address(self.reg.pc),
             AddressingMode::Accumulator => Operand::Accumulator,
 
-            AddressingMode::ZeroPage => Operand::Address(self.bus.read(self.reg.pc) as u16),
+            AddressingMode::ZeroPage => Operand::Address(self.fetch(self.reg.pc) as u16),
             AddressingMode::ZeroPageX => {
-                Operand::Address(self.bus.read(self.reg.pc).wrapping_add(self.reg.x) as u16)
+                Operand::Address(self.fetch(self.reg.pc).wrapping_add(self.reg.x) as u16)
             }
             AddressingMode::ZeroPageY => {
-                Operand::Address(self.bus.read(self.reg.pc).wrapping_add(self.reg.y) as u16)
+                Operand::Address(self.fetch(self.reg.pc).wrapping_add(self.reg.y) as u16)
             }
 
-            AddressingMode::Absolute => Operand::Address(self.bus.read_u16(self.reg.pc)),
+            AddressingMode::Absolute => Operand::Address(self.fetch_u16(self.reg.pc)),
             AddressingMode::AbsoluteX => {
                 if let Some(modifier) = opcode.tick_modifier {
-                    let lo = self.bus.read(self.reg.pc);
-                    let hi = self.bus.read(self.reg.pc + 1);
+                    let lo = self.fetch(self.reg.pc);
+                    let hi = self.fetch(self.reg.pc.wrapping_add(1));
 
                     return self.on_tick_modifier(lo, hi, self.reg.x, modifier);
                 }
 
-                Operand::Address(
-                    self.bus
-                        .read_u16(self.reg.pc)
-                        .wrapping_add(self.reg.x as u16),
-                )
+                Operand::Address(self.fetch_u16(self.reg.pc).wrapping_add(self.reg.x as u16))
             }
             AddressingMode::AbsoluteY => {
                 if let Some(modifier) = opcode.tick_modifier {
-                    let lo = self.bus.read(self.reg.pc);
-                    let hi = self.bus.read(self.reg.pc + 1);
+                    let lo = self.fetch(self.reg.pc);
+                    let hi = self.fetch(self.reg.pc.wrapping_add(1));
 
                     return self.on_tick_modifier(lo, hi, self.reg.y, modifier);
                 }
 
-                Operand::Address(
-                    self.bus
-                        .read_u16(self.reg.pc)
-                        .wrapping_add(self.reg.y as u16),
-                )
+                Operand::Address(self.fetch_u16(self.reg.pc).wrapping_add(self.reg.y as u16))
             }
 
             AddressingMode::Indirect => {
-                Operand::Address(self.bus.read_u16(self.bus.read_u16(self.reg.pc)))
+                let ptr = self.fetch_u16(self.reg.pc);
+                let lo = self.read(ptr);
+                // 6502 indirect jump bug
+                let hi = self.read(ptr & 0xff00 | (ptr as u8).wrapping_add(1) as u16);
+                Operand::Address(u16::from_le_bytes([lo, hi]))
+            }
+            AddressingMode::IndirectX => {
+                let ptr = self.fetch(self.reg.pc).wrapping_add(self.reg.x);
+                Operand::Address(self.read_zp_u16(ptr))
             }
-            AddressingMode::IndirectX => Operand::Address(
-                self.bus
-                    .read_u16(self.bus.read(self.reg.pc).wrapping_add(self.reg.x) as u16),
-            ),
             AddressingMode::IndirectY => {
-                if let Some(modifier) = opcode.tick_modifier {
-                    let lo = self.bus.read(self.bus.read(self.reg.pc) as u16);
-                    let hi = self.bus.read(self.bus.read(self.reg.pc) as u16 + 1);
+                let ptr = self.fetch(self.reg.pc);
 
+                if let Some(modifier) = opcode.tick_modifier {
+                    let [lo, hi] = self.read_zp_u16(ptr).to_le_bytes();
                     return self.on_tick_modifier(lo, hi, self.reg.y, modifier);
                 }
 
-                Operand::Address(
-                    self.bus
-                        .read_u16(self.bus.read(self.reg.pc) as u16)
-                        .wrapping_add(self.reg.y as u16),
-                )
+                Operand::Address(self.read_zp_u16(ptr).wrapping_add(self.reg.y as u16))
             }
 
             _ => unreachable!(),
@@ -342,7 +472,7 @@ impl CPU {
         let c = self.reg.p.contains(Flags::CARRY) as u16;
 
         if let Operand::Address(addr) = self.get_operand(opcode) {
-            let n = self.bus.read(addr) as u16;
+            let n = self.read_operand(opcode, addr) as u16;
 
             if self.reg.p.contains(Flags::DECIMAL) {
                 let mut l = (m & 0x0f) + (n & 0x0f) + c;
@@ -373,7 +503,7 @@ impl CPU {
 
     fn and(&mut self, opcode: &Opcode) {
         if let Operand::Address(addr) = self.get_operand(opcode) {
-            self.reg.a &= self.bus.read(addr);
+            self.reg.a &= self.read_operand(opcode, addr);
             self.update_nz_flags(self.reg.a);
         }
     }
@@ -384,15 +514,16 @@ impl CPU {
                 self.reg.a = self._asl(self.reg.a);
             }
             Operand::Address(addr) => {
-                let byte = self._asl(self.bus.read(addr));
-                self.bus.write(addr, byte);
+                let byte = self.read(addr);
+                let byte = self._asl(byte);
+                self.write(addr, byte);
             }
         }
     }
 
     fn bit(&mut self, opcode: &Opcode) {
         if let Operand::Address(addr) = self.get_operand(opcode) {
-            let operand = self.bus.read(addr);
+            let operand = self.read(addr);
             let result = self.reg.a & operand;
 
             self.update_nz_flags(result);
@@ -411,11 +542,9 @@ impl CPU {
         if let Operand::Address(addr) = self.get_operand(opcode) {
             let page = self.reg.pc >> 8;
 
-            self.reg.pc = self
-                .reg
-                .pc
-                .wrapping_add(1)
-                .wrapping_add(i8::from_le_bytes(self.bus.read(addr).to_le_bytes()) as u16);
+            self.reg.pc = self.reg.pc.wrapping_add(1).wrapping_add(i8::from_le_bytes(
+                self.read_operand(opcode, addr).to_le_bytes(),
+            ) as u16);
 
             if page != self.reg.pc >> 8 {
                 self.cycle += 1;
@@ -425,7 +554,7 @@ impl CPU {
 
     fn cmp(&mut self, opcode: &Opcode, reg: u8) {
         if let Operand::Address(addr) = self.get_operand(opcode) {
-            let operand = self.bus.read(addr);
+            let operand = self.read_operand(opcode, addr);
 
             self.set_flag(Flags::ZERO, reg == operand);
             self.set_flag(Flags::CARRY, reg >= operand);
@@ -435,8 +564,8 @@ impl CPU {
 
     fn dec(&mut self, opcode: &Opcode) {
         if let Operand::Address(addr) = self.get_operand(opcode) {
-            let value = self.bus.read(addr).wrapping_sub(1);
-            self.bus.write(addr, value);
+            let value = self.read(addr).wrapping_sub(1);
+            self.write(addr, value);
             self.update_nz_flags(value);
         }
     }
@@ -453,15 +582,15 @@ impl CPU {
 
     fn eor(&mut self, opcode: &Opcode) {
         if let Operand::Address(addr) = self.get_operand(opcode) {
-            self.reg.a ^= self.bus.read(addr);
+            self.reg.a ^= self.read_operand(opcode, addr);
             self.update_nz_flags(self.reg.a);
         }
     }
 
     fn inc(&mut self, opcode: &Opcode) {
         if let Operand::Address(addr) = self.get_operand(opcode) {
-            let value = self.bus.read(addr).wrapping_add(1);
-            self.bus.write(addr, value);
+            let value = self.read(addr).wrapping_add(1);
+            self.write(addr, value);
             self.update_nz_flags(value);
         }
     }
@@ -477,21 +606,9 @@ impl CPU {
     }
 
     fn jmp(&mut self, opcode: &Opcode) {
-        let operand = self.bus.read_u16(self.reg.pc);
-
-        if opcode.code == 0x6c {
-            if operand & 0xff != 0xff {
-                return self.reg.pc = self.bus.read_u16(operand);
-            }
-
-            // 6502 indirect jump bug
-            let lo = self.bus.read(operand) as u16;
-            let hi = self.bus.read(operand & 0xff00) as u16;
-
-            return self.reg.pc = (hi << 8) | lo;
+        if let Operand::Address(addr) = self.get_operand(opcode) {
+            self.reg.pc = addr;
         }
-
-        self.reg.pc = operand;
     }
 
     fn jsr(&mut self, opcode: &Opcode) {
@@ -503,21 +620,21 @@ impl CPU {
 
     fn lda(&mut self, opcode: &Opcode) {
         if let Operand::Address(addr) = self.get_operand(opcode) {
-            self.reg.a = self.bus.read(addr);
+            self.reg.a = self.read_operand(opcode, addr);
             self.update_nz_flags(self.reg.a);
         }
     }
 
     fn ldx(&mut self, opcode: &Opcode) {
         if let Operand::Address(addr) = self.get_operand(opcode) {
-            self.reg.x = self.bus.read(addr);
+            self.reg.x = self.read_operand(opcode, addr);
             self.update_nz_flags(self.reg.x);
         }
     }
 
     fn ldy(&mut self, opcode: &Opcode) {
         if let Operand::Address(addr) = self.get_operand(opcode) {
-            self.reg.y = self.bus.read(addr);
+            self.reg.y = self.read_operand(opcode, addr);
             self.update_nz_flags(self.reg.y);
         }
     }
@@ -528,15 +645,16 @@ impl CPU {
                 self.reg.a = self._lsr(self.reg.a);
             }
             Operand::Address(addr) => {
-                let byte = self._lsr(self.bus.read(addr));
-                self.bus.write(addr, byte);
+                let byte = self.read(addr);
+                let byte = self._lsr(byte);
+                self.write(addr, byte);
             }
         }
     }
 
     fn ora(&mut self, opcode: &Opcode) {
         if let Operand::Address(addr) = self.get_operand(opcode) {
-            self.reg.a |= self.bus.read(addr);
+            self.reg.a |= self.read_operand(opcode, addr);
             self.update_nz_flags(self.reg.a);
         }
     }
@@ -564,8 +682,9 @@ impl CPU {
                 self.reg.a = self._rol(self.reg.a);
             }
             Operand::Address(addr) => {
-                let byte = self._rol(self.bus.read(addr));
-                self.bus.write(addr, byte);
+                let byte = self.read(addr);
+                let byte = self._rol(byte);
+                self.write(addr, byte);
             }
         }
     }
@@ -576,8 +695,9 @@ impl CPU {
                 self.reg.a = self._ror(self.reg.a);
             }
             Operand::Address(addr) => {
-                let byte = self._ror(self.bus.read(addr));
-                self.bus.write(addr, byte);
+                let byte = self.read(addr);
+                let byte = self._ror(byte);
+                self.write(addr, byte);
             }
         }
     }
@@ -597,7 +717,7 @@ impl CPU {
         let c = self.reg.p.contains(Flags::CARRY) as u8;
 
         if let Operand::Address(addr) = self.get_operand(opcode) {
-            let n = self.bus.read(addr);
+            let n = self.read_operand(opcode, addr);
             let mut s =  m as u16
                       + !n as u16
                       +  c as u16;
@@ -623,7 +743,7 @@ impl CPU {
 
     fn str(&mut self, opcode: &Opcode, reg: u8) {
         if let Operand::Address(addr) = self.get_operand(opcode) {
-            self.bus.write(addr, reg);
+            self.write(addr, reg);
         }
     }
 
@@ -0,0 +1,245 @@
+use crate::cpu::{Flags, Registers};
+use core::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConditionError {
+    UnexpectedEnd,
+    UnexpectedToken(String),
+    InvalidNumber(String),
+}
+
+impl fmt::Display for ConditionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConditionError::UnexpectedEnd => write!(f, "unexpected end of condition"),
+            ConditionError::UnexpectedToken(token) => write!(f, "unexpected token: {token}"),
+            ConditionError::InvalidNumber(number) => write!(f, "invalid number: {number}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value {
+    A,
+    X,
+    Y,
+    SP,
+    PC,
+    P,
+    Flag(Flags),
+    Number(u16),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+// register conditions like `A == $40 && X > 3`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    Compare(Value, Comparison, Value),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+impl Value {
+    fn eval(&self, reg: &Registers) -> u16 {
+        match self {
+            Value::A => reg.a as u16,
+            Value::X => reg.x as u16,
+            Value::Y => reg.y as u16,
+            Value::SP => reg.sp as u16,
+            Value::PC => reg.pc,
+            Value::P => reg.p.bits() as u16,
+            Value::Flag(flag) => reg.p.contains(*flag) as u16,
+            Value::Number(n) => *n,
+        }
+    }
+}
+
+impl Condition {
+    pub fn parse(source: &str) -> Result<Self, ConditionError> {
+        let mut parser = Parser {
+            tokens: tokenize(source),
+            current: 0,
+        };
+        let condition = parser.or()?;
+
+        match parser.peek() {
+            None => Ok(condition),
+            Some(token) => Err(ConditionError::UnexpectedToken(token.to_owned())),
+        }
+    }
+
+    pub fn eval(&self, reg: &Registers) -> bool {
+        match self {
+            Condition::And(lhs, rhs) => lhs.eval(reg) && rhs.eval(reg),
+            Condition::Or(lhs, rhs) => lhs.eval(reg) || rhs.eval(reg),
+            Condition::Compare(lhs, cmp, rhs) => {
+                let (lhs, rhs) = (lhs.eval(reg), rhs.eval(reg));
+
+                match cmp {
+                    Comparison::Eq => lhs == rhs,
+                    Comparison::Ne => lhs != rhs,
+                    Comparison::Lt => lhs < rhs,
+                    Comparison::Le => lhs <= rhs,
+                    Comparison::Gt => lhs > rhs,
+                    Comparison::Ge => lhs >= rhs,
+                }
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for Condition {
+    type Err = ConditionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Condition::parse(s)
+    }
+}
+
+fn tokenize(source: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => continue,
+            '(' | ')' => tokens.push(c.to_string()),
+            '=' | '!' | '<' | '>' | '&' | '|' => {
+                let mut token = c.to_string();
+                if let Some(next @ ('=' | '&' | '|')) = chars.peek().copied() {
+                    token.push(next);
+                    chars.next();
+                }
+                tokens.push(token);
+            }
+            _ => {
+                let mut token = c.to_string();
+                while let Some(next) = chars.peek().copied() {
+                    if !(next.is_ascii_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    token.push(next);
+                    chars.next();
+                }
+                tokens.push(token);
+            }
+        }
+    }
+
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    current: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.current).map(|token| token.as_str())
+    }
+
+    fn advance(&mut self) -> Result<&str, ConditionError> {
+        let token = self
+            .tokens
+            .get(self.current)
+            .ok_or(ConditionError::UnexpectedEnd)?;
+        self.current += 1;
+
+        Ok(token.as_str())
+    }
+
+    fn or(&mut self) -> Result<Condition, ConditionError> {
+        let mut lhs = self.and()?;
+
+        while self.peek() == Some("||") {
+            self.current += 1;
+            lhs = Condition::Or(Box::new(lhs), Box::new(self.and()?));
+        }
+
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Condition, ConditionError> {
+        let mut lhs = self.comparison()?;
+
+        while self.peek() == Some("&&") {
+            self.current += 1;
+            lhs = Condition::And(Box::new(lhs), Box::new(self.comparison()?));
+        }
+
+        Ok(lhs)
+    }
+
+    fn comparison(&mut self) -> Result<Condition, ConditionError> {
+        if self.peek() == Some("(") {
+            self.current += 1;
+            let condition = self.or()?;
+
+            return match self.advance()? {
+                ")" => Ok(condition),
+                token => Err(ConditionError::UnexpectedToken(token.to_owned())),
+            };
+        }
+
+        let lhs = self.value()?;
+        let cmp = match self.advance()? {
+            "==" | "=" => Comparison::Eq,
+            "!=" => Comparison::Ne,
+            "<" => Comparison::Lt,
+            "<=" => Comparison::Le,
+            ">" => Comparison::Gt,
+            ">=" => Comparison::Ge,
+            token => return Err(ConditionError::UnexpectedToken(token.to_owned())),
+        };
+        let rhs = self.value()?;
+
+        Ok(Condition::Compare(lhs, cmp, rhs))
+    }
+
+    fn value(&mut self) -> Result<Value, ConditionError> {
+        let token = self.advance()?;
+
+        let value = match token.to_uppercase().as_str() {
+            "A" => Value::A,
+            "X" => Value::X,
+            "Y" => Value::Y,
+            "SP" => Value::SP,
+            "PC" => Value::PC,
+            "P" => Value::P,
+            "N" => Value::Flag(Flags::NEGATIVE),
+            "V" => Value::Flag(Flags::OVERFLOW),
+            "B" => Value::Flag(Flags::BREAK),
+            "D" => Value::Flag(Flags::DECIMAL),
+            "I" => Value::Flag(Flags::INTERRUPT),
+            "Z" => Value::Flag(Flags::ZERO),
+            "C" => Value::Flag(Flags::CARRY),
+            _ => Value::Number(parse_number(token)?),
+        };
+
+        Ok(value)
+    }
+}
+
+fn parse_number(token: &str) -> Result<u16, ConditionError> {
+    let (digits, radix) = if let Some(hex) = token.strip_prefix('$') {
+        (hex, 16)
+    } else if let Some(hex) = token.strip_prefix("0x") {
+        (hex, 16)
+    } else if let Some(bin) = token.strip_prefix('%') {
+        (bin, 2)
+    } else {
+        (token, 10)
+    };
+
+    u16::from_str_radix(digits, radix).map_err(|_| ConditionError::InvalidNumber(token.to_owned()))
+}
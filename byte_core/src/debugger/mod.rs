@@ -0,0 +1,415 @@
+mod condition;
+
+pub use condition::{Comparison, Condition, ConditionError, Value};
+
+use crate::cpu::{Interrupt, Registers, CPU};
+use crate::Error;
+
+use bitflags::bitflags;
+use std::collections::BTreeMap;
+
+bitflags! {
+    #[derive(Default)]
+    pub struct Access: u8 {
+        const READ    = 0b001;
+        const WRITE   = 0b010;
+        const EXECUTE = 0b100;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    Breakpoint(u16),
+    // `pc` is the instruction that accessed `addr`, or the first one of the
+    // handler when an interrupt did
+    Watchpoint { addr: u16, access: Access, pc: u16 },
+    // the instruction at `pc` wrote to read-only memory at `addr`
+    WriteFault { addr: u16, pc: u16 },
+    // about to execute a `BRK` instruction at the given address
+    Brk(u16),
+    // the goal set with `CPU::set_goal` was reached
+    Goal,
+    // the instruction budget given to `run_until_break` ran out
+    Limit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    // stop before the next instruction
+    Step,
+    // like `Step`, but runs subroutine calls to completion
+    StepOver,
+    // run until the current subroutine or interrupt handler returns
+    StepOut,
+    RunTo(u16),
+}
+
+// `Goal` after it has been resolved against the state of the cpu
+#[derive(Debug, Clone, Copy)]
+enum Target {
+    Instructions(u32),
+    Address { pc: u16, depth: usize },
+    Depth(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+    // address of the `JSR` instruction, or the pc that got interrupted
+    pub caller: u16,
+    pub target: u16,
+    // stack pointer right after the return address was pushed
+    pub sp: u8,
+    pub interrupt: Option<Interrupt>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Breakpoint {
+    pub addr: u16,
+    pub enabled: bool,
+    pub condition: Option<Condition>,
+    // the number of hits to let through before actually breaking
+    pub ignore: u32,
+    pub hits: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Watchpoint {
+    pub range: (u16, u16),
+    pub access: Access,
+    pub enabled: bool,
+    pub condition: Option<Condition>,
+    pub ignore: u32,
+    pub hits: u32,
+}
+
+#[derive(Default)]
+pub struct Debugger {
+    breakpoints: BTreeMap<u16, Breakpoint>,
+    watchpoints: Vec<Watchpoint>,
+    // pc of the instruction we last stopped at, so that resuming
+    // doesn't immediately trigger the same breakpoint again
+    stopped_at: Option<u16>,
+    // memory watchpoint triggered by the instruction being executed, or by
+    // an interrupt since the last one
+    pending: Option<(u16, Access)>,
+    target: Option<Target>,
+    call_stack: Vec<Frame>,
+    break_on_fault: bool,
+    break_on_brk: bool,
+}
+
+impl Breakpoint {
+    pub fn new(addr: u16) -> Self {
+        Self {
+            addr,
+            enabled: true,
+            condition: None,
+            ignore: 0,
+            hits: 0,
+        }
+    }
+
+    pub fn with_condition(mut self, condition: Condition) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+
+    pub fn with_ignore(mut self, ignore: u32) -> Self {
+        self.ignore = ignore;
+        self
+    }
+}
+
+impl Watchpoint {
+    pub fn new(lo: u16, hi: u16, access: Access) -> Self {
+        Self {
+            range: (lo, hi),
+            access,
+            enabled: true,
+            condition: None,
+            ignore: 0,
+            hits: 0,
+        }
+    }
+
+    pub fn with_condition(mut self, condition: Condition) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+
+    pub fn with_ignore(mut self, ignore: u32) -> Self {
+        self.ignore = ignore;
+        self
+    }
+
+    fn matches(&self, addr: u16, access: Access) -> bool {
+        self.enabled
+            && self.access.intersects(access)
+            && self.range.0 <= addr
+            && self.range.1 >= addr
+    }
+}
+
+// counts the hit and decides whether it should stop the cpu
+fn hit(condition: &Option<Condition>, ignore: u32, hits: &mut u32, reg: &Registers) -> bool {
+    if let Some(condition) = condition {
+        if !condition.eval(reg) {
+            return false;
+        }
+    }
+
+    *hits += 1;
+    *hits > ignore
+}
+
+impl Debugger {
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        self.breakpoints.insert(breakpoint.addr, breakpoint);
+    }
+
+    pub fn remove_breakpoint(&mut self, addr: u16) -> Option<Breakpoint> {
+        self.breakpoints.remove(&addr)
+    }
+
+    pub fn toggle_breakpoint(&mut self, addr: u16) {
+        if self.breakpoints.remove(&addr).is_none() {
+            self.add_breakpoint(Breakpoint::new(addr));
+        }
+    }
+
+    pub fn breakpoint(&self, addr: u16) -> Option<&Breakpoint> {
+        self.breakpoints.get(&addr)
+    }
+
+    pub fn breakpoint_mut(&mut self, addr: u16) -> Option<&mut Breakpoint> {
+        self.breakpoints.get_mut(&addr)
+    }
+
+    pub fn breakpoints(&self) -> impl Iterator<Item = &Breakpoint> {
+        self.breakpoints.values()
+    }
+
+    pub fn add_watchpoint(&mut self, watchpoint: Watchpoint) -> usize {
+        self.watchpoints.push(watchpoint);
+        self.watchpoints.len() - 1
+    }
+
+    pub fn remove_watchpoint(&mut self, index: usize) -> Option<Watchpoint> {
+        (index < self.watchpoints.len()).then(|| self.watchpoints.remove(index))
+    }
+
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
+
+    pub fn watchpoints_mut(&mut self) -> &mut [Watchpoint] {
+        &mut self.watchpoints
+    }
+
+    pub fn clear(&mut self) {
+        self.breakpoints.clear();
+        self.watchpoints.clear();
+        self.stopped_at = None;
+        self.pending = None;
+        self.target = None;
+    }
+
+    // stop when the program writes to rom instead of silently ignoring it
+    pub fn set_break_on_fault(&mut self, enabled: bool) {
+        self.break_on_fault = enabled;
+    }
+
+    pub fn breaks_on_fault(&self) -> bool {
+        self.break_on_fault
+    }
+
+    pub fn set_break_on_brk(&mut self, enabled: bool) {
+        self.break_on_brk = enabled;
+    }
+
+    pub fn breaks_on_brk(&self) -> bool {
+        self.break_on_brk
+    }
+
+    // subroutine calls and interrupts that haven't returned yet, innermost last
+    pub fn call_stack(&self) -> &[Frame] {
+        &self.call_stack
+    }
+
+    pub fn has_goal(&self) -> bool {
+        self.target.is_some()
+    }
+
+    pub fn cancel_goal(&mut self) {
+        self.target = None;
+    }
+
+    pub(crate) fn on_interrupt(&mut self, interrupt: Interrupt, caller: u16, reg: &Registers) {
+        if interrupt == Interrupt::RST {
+            self.call_stack.clear();
+            return;
+        }
+
+        self.unwind(reg.sp);
+        self.call_stack.push(Frame {
+            caller,
+            target: reg.pc,
+            sp: reg.sp,
+            interrupt: Some(interrupt),
+        });
+    }
+
+    // drops the frames whose return address has already been pulled off
+    // the stack. going by the stack pointer instead of matching `RTS` and
+    // `RTI` keeps this correct for code that manipulates the stack itself.
+    fn unwind(&mut self, sp: u8) {
+        while let Some(frame) = self.call_stack.last() {
+            if frame.sp >= sp {
+                break;
+            }
+            self.call_stack.pop();
+        }
+    }
+
+    fn goal_reached(&mut self, reg: &Registers) -> bool {
+        let reached = match self.target {
+            None => return false,
+            Some(Target::Instructions(n)) => n == 0,
+            Some(Target::Address { pc, depth }) => reg.pc == pc && self.call_stack.len() <= depth,
+            Some(Target::Depth(depth)) => self.call_stack.len() < depth,
+        };
+
+        if reached {
+            self.target = None;
+        }
+        reached
+    }
+
+    // called by the cpu on every memory access
+    #[inline]
+    pub(crate) fn on_access(&mut self, addr: u16, access: Access, reg: &Registers) {
+        if self.watchpoints.is_empty() || self.pending.is_some() {
+            return;
+        }
+
+        for wp in self.watchpoints.iter_mut() {
+            if wp.matches(addr, access) && hit(&wp.condition, wp.ignore, &mut wp.hits, reg) {
+                self.pending = Some((addr, access));
+                return;
+            }
+        }
+    }
+
+    fn check_pc(&mut self, reg: &Registers, code: u8) -> Option<StopReason> {
+        let pc = reg.pc;
+
+        if self.goal_reached(reg) {
+            return Some(StopReason::Goal);
+        }
+
+        if self.stopped_at.take() == Some(pc) {
+            return None;
+        }
+
+        if code == 0x00 && self.break_on_brk {
+            return Some(StopReason::Brk(pc));
+        }
+
+        if let Some(bp) = self.breakpoints.get_mut(&pc) {
+            if bp.enabled && hit(&bp.condition, bp.ignore, &mut bp.hits, reg) {
+                return Some(StopReason::Breakpoint(pc));
+            }
+        }
+
+        for wp in self.watchpoints.iter_mut() {
+            if wp.matches(pc, Access::EXECUTE) && hit(&wp.condition, wp.ignore, &mut wp.hits, reg) {
+                return Some(StopReason::Watchpoint {
+                    addr: pc,
+                    access: Access::EXECUTE,
+                    pc,
+                });
+            }
+        }
+
+        None
+    }
+}
+
+impl CPU {
+    // executes a single instruction unless a breakpoint, an execute
+    // watchpoint or a `BRK` stops it. memory watchpoints and write faults
+    // stop after the instruction that triggered them has finished.
+    pub fn debug_step(&mut self) -> Result<Option<StopReason>, Error> {
+        let pc = self.reg.pc;
+        let code = self.bus.peek(pc);
+
+        // an interrupt touches memory between instructions
+        if let Some((addr, access)) = self.debugger.pending.take() {
+            return Ok(Some(StopReason::Watchpoint { addr, access, pc }));
+        }
+
+        if let Some(reason) = self.debugger.check_pc(&self.reg, code) {
+            self.debugger.stopped_at = Some(pc);
+            return Ok(Some(reason));
+        }
+
+        self.bus.take_fault();
+        self.step()?;
+
+        let debugger = &mut self.debugger;
+        debugger.unwind(self.reg.sp);
+        if code == 0x20 {
+            debugger.call_stack.push(Frame {
+                caller: pc,
+                target: self.reg.pc,
+                sp: self.reg.sp,
+                interrupt: None,
+            });
+        }
+        if let Some(Target::Instructions(n)) = debugger.target.as_mut() {
+            *n = n.saturating_sub(1);
+        }
+
+        if let Some(addr) = self.bus.take_fault() {
+            if self.debugger.break_on_fault {
+                self.debugger.pending = None;
+                return Ok(Some(StopReason::WriteFault { addr, pc }));
+            }
+        }
+
+        Ok(self
+            .debugger
+            .pending
+            .take()
+            .map(|(addr, access)| StopReason::Watchpoint { addr, access, pc }))
+    }
+
+    pub fn set_goal(&mut self, goal: Goal) {
+        let depth = self.debugger.call_stack.len();
+
+        self.debugger.target = Some(match goal {
+            Goal::Step => Target::Instructions(1),
+            Goal::StepOver if self.bus.peek(self.reg.pc) == 0x20 => Target::Address {
+                pc: self.reg.pc.wrapping_add(3),
+                depth,
+            },
+            Goal::StepOver => Target::Instructions(1),
+            Goal::StepOut => Target::Depth(depth),
+            // `depth` doesn't matter when running to an arbitrary address
+            Goal::RunTo(pc) => Target::Address {
+                pc,
+                depth: usize::MAX,
+            },
+        });
+    }
+
+    pub fn run_until_break(&mut self, max_instructions: usize) -> Result<StopReason, Error> {
+        for _ in 0..max_instructions {
+            if let Some(reason) = self.debug_step()? {
+                return Ok(reason);
+            }
+        }
+
+        Ok(StopReason::Limit)
+    }
+}
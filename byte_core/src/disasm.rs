@@ -0,0 +1,117 @@
+use byte_common::opcode::*;
+use core::fmt;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Instruction {
+    pub addr: u16,
+    pub opcode: Option<Opcode>,
+    pub bytes: [u8; 3],
+}
+
+impl Instruction {
+    pub fn decode<F>(addr: u16, read: F) -> Self
+    where
+        F: Fn(u16) -> u8,
+    {
+        let code = read(addr);
+        let opcode = OPCODE_MAP.get(code as usize).copied().flatten();
+
+        Self {
+            addr,
+            opcode,
+            bytes: [code, read(addr.wrapping_add(1)), read(addr.wrapping_add(2))],
+        }
+    }
+
+    pub fn size(&self) -> u16 {
+        self.opcode.map_or(1, |opcode| opcode.size as u16)
+    }
+
+    pub fn next(&self) -> u16 {
+        self.addr.wrapping_add(self.size())
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes[..self.size() as usize]
+    }
+
+    // the 8 or 16 bit value following the opcode
+    pub fn operand(&self) -> u16 {
+        match self.size() {
+            2 => self.bytes[1] as u16,
+            3 => u16::from_le_bytes([self.bytes[1], self.bytes[2]]),
+            _ => 0,
+        }
+    }
+
+    pub fn branch_target(&self) -> u16 {
+        self.next().wrapping_add(self.bytes[1] as i8 as u16)
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(opcode) = self.opcode else {
+            return write!(f, ".DB ${:02X}", self.bytes[0]);
+        };
+        let value = self.operand();
+
+        write!(f, "{:?}", opcode.mnemonic)?;
+        match opcode.mode {
+            AddressingMode::Implied => Ok(()),
+            AddressingMode::Accumulator => write!(f, " A"),
+            AddressingMode::Immediate => write!(f, " #${value:02X}"),
+            AddressingMode::Relative => write!(f, " ${:04X}", self.branch_target()),
+            AddressingMode::ZeroPage => write!(f, " ${value:02X}"),
+            AddressingMode::ZeroPageX => write!(f, " ${value:02X},X"),
+            AddressingMode::ZeroPageY => write!(f, " ${value:02X},Y"),
+            AddressingMode::Absolute => write!(f, " ${value:04X}"),
+            AddressingMode::AbsoluteX => write!(f, " ${value:04X},X"),
+            AddressingMode::AbsoluteY => write!(f, " ${value:04X},Y"),
+            AddressingMode::Indirect => write!(f, " (${value:04X})"),
+            AddressingMode::IndirectX => write!(f, " (${value:02X},X)"),
+            AddressingMode::IndirectY => write!(f, " (${value:02X}),Y"),
+        }
+    }
+}
+
+// finds an address up to `lines` instructions before `anchor` that decodes
+// into a stream of instructions landing exactly on `anchor`. code mixed
+// with data can decode in several ways, so every possible start is tried
+// and the one producing the fewest unknown opcodes wins.
+pub fn find_start<F>(anchor: u16, lines: usize, read: F) -> u16
+where
+    F: Fn(u16) -> u8,
+{
+    let max_distance = (lines * 3) as u16;
+    let mut best: Option<(usize, Vec<u16>)> = None;
+
+    for distance in (1..=max_distance).rev() {
+        let mut addr = anchor.wrapping_sub(distance);
+        let mut starts = Vec::new();
+        let mut unknown = 0;
+
+        while anchor.wrapping_sub(addr) != 0 && anchor.wrapping_sub(addr) <= distance {
+            let instruction = Instruction::decode(addr, &read);
+            if instruction.opcode.is_none() {
+                unknown += 1;
+            }
+
+            starts.push(addr);
+            addr = instruction.next();
+        }
+
+        if addr != anchor {
+            continue;
+        }
+        match best {
+            Some((fewest, _)) if fewest <= unknown => (),
+            _ => best = Some((unknown, starts)),
+        }
+    }
+
+    match best {
+        Some((_, starts)) if lines > 0 => starts[starts.len().saturating_sub(lines)],
+        _ => anchor,
+    }
+}
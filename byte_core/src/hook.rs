@@ -0,0 +1,25 @@
+use crate::bus::Bus;
+use crate::cpu::Registers;
+use byte_common::opcode::Opcode;
+
+#[derive(Debug, Clone, Copy)]
+pub struct StepInfo {
+    // address of the opcode being executed
+    pub pc: u16,
+    pub opcode: Opcode,
+    // effective address of the operand, `None` for implied and accumulator modes
+    pub operand: Option<u16>,
+    pub reg: Registers,
+    pub cycle: u64,
+}
+
+// every method has an empty default implementation, so a hook only
+// needs to implement the events it cares about. `before_instruction`
+// receives the registers as they were before the instruction ran,
+// `after_instruction` receives them after.
+pub trait Hook {
+    fn before_instruction(&mut self, _info: &StepInfo, _bus: &Bus) {}
+    fn after_instruction(&mut self, _info: &StepInfo, _bus: &Bus) {}
+    fn on_read(&mut self, _addr: u16, _byte: u8) {}
+    fn on_write(&mut self, _addr: u16, _byte: u8) {}
+}
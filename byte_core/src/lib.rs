@@ -1,5 +1,9 @@
 pub mod bus;
 pub mod cpu;
+pub mod debugger;
+pub mod disasm;
+pub mod hook;
+pub mod trace;
 
 #[derive(Debug)]
 pub enum Error {
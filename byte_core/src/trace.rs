@@ -0,0 +1,86 @@
+use crate::bus::Bus;
+use crate::disasm::Instruction;
+use crate::hook::{Hook, StepInfo};
+use byte_common::opcode::{AddressingMode, Mnemonic};
+use std::io::Write;
+
+// writes a nestest-style line for every executed instruction:
+// C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD CYC:7
+pub struct TraceLogger<W: Write> {
+    out: W,
+}
+
+impl<W: Write> TraceLogger<W> {
+    pub fn new(out: W) -> Self {
+        Self { out }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    pub fn format(info: &StepInfo, bus: &Bus) -> String {
+        let instruction = Instruction::decode(info.pc, |addr| bus.peek(addr));
+        let bytes = instruction
+            .bytes()
+            .iter()
+            .map(|b| format!("{b:02X}"))
+            .collect::<Vec<String>>()
+            .join(" ");
+        let text = format!("{instruction}{}", annotate(info, &instruction, bus));
+
+        format!(
+            "{:04X}  {bytes:<8}  {text:<32}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} CYC:{}",
+            info.pc,
+            info.reg.a,
+            info.reg.x,
+            info.reg.y,
+            info.reg.p.bits(),
+            info.reg.sp,
+            info.cycle,
+        )
+    }
+}
+
+impl<W: Write> Hook for TraceLogger<W> {
+    fn before_instruction(&mut self, info: &StepInfo, bus: &Bus) {
+        // tracing shouldn't be able to stop the emulation, ignore io errors
+        writeln!(self.out, "{}", Self::format(info, bus)).ok();
+    }
+}
+
+// the memory annotations nestest appends after the operand
+fn annotate(info: &StepInfo, instruction: &Instruction, bus: &Bus) -> String {
+    let Some(addr) = info.operand else {
+        return String::new();
+    };
+    let value = bus.peek(addr);
+    let operand = instruction.operand();
+
+    match info.opcode.mode {
+        AddressingMode::ZeroPage | AddressingMode::Absolute => match info.opcode.mnemonic {
+            Mnemonic::JMP | Mnemonic::JSR => String::new(),
+            _ => format!(" = {value:02X}"),
+        },
+        AddressingMode::ZeroPageX
+        | AddressingMode::ZeroPageY
+        | AddressingMode::AbsoluteX
+        | AddressingMode::AbsoluteY => {
+            if info.opcode.size == 2 {
+                format!(" @ {addr:02X} = {value:02X}")
+            } else {
+                format!(" @ {addr:04X} = {value:02X}")
+            }
+        }
+        AddressingMode::Indirect => format!(" = {addr:04X}"),
+        AddressingMode::IndirectX => {
+            let ptr = (operand as u8).wrapping_add(info.reg.x);
+            format!(" @ {ptr:02X} = {addr:04X} = {value:02X}")
+        }
+        AddressingMode::IndirectY => {
+            let base = addr.wrapping_sub(info.reg.y as u16);
+            format!(" = {base:04X} @ {addr:04X} = {value:02X}")
+        }
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,292 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+mod common;
+
+use common::bus::{BusError, Mapper, Peripheral, Region};
+
+// a status register that clears itself once the cpu has read it
+#[derive(Default)]
+struct Status {
+    flags: u8,
+}
+
+impl Peripheral for Status {
+    fn peek(&self, _addr: u16) -> u8 {
+        self.flags
+    }
+
+    fn read(&mut self, _addr: u16) -> u8 {
+        std::mem::take(&mut self.flags)
+    }
+
+    fn write(&mut self, _addr: u16, byte: u8) {
+        self.flags |= byte;
+    }
+}
+
+fn init() -> common::cpu::CPU {
+    let mut cpu = common::cpu::CPU::default();
+    cpu.bus.attach(0x0000, 0x00ff, common::MockRAM::new(0x100)).unwrap();
+    cpu.bus.attach(0x0200, 0x0200, Status::default()).unwrap();
+    cpu.bus.attach(0x8000, 0xffff, common::MockRAM::new(0x8000)).unwrap();
+    cpu
+}
+
+#[test]
+fn reads_have_side_effects() {
+    let mut cpu = init();
+    cpu.bus.write(0x0200, 0x81);
+
+    assert_eq!(cpu.bus.read(0x0200), 0x81);
+    assert_eq!(cpu.bus.read(0x0200), 0x00);
+}
+
+#[test]
+fn peek_has_no_side_effects() {
+    let mut cpu = init();
+    cpu.bus.write(0x0200, 0x81);
+
+    assert_eq!(cpu.bus.peek(0x0200), 0x81);
+    assert_eq!(cpu.bus.peek(0x0200), 0x81);
+}
+
+#[test]
+fn cpu_reads_go_through_the_mutable_path() {
+    let mut cpu = init();
+    cpu.bus.write(0x0200, 0x40);
+
+    // LDA $0200, LDX $0200
+    cpu.reg.pc = 0x8000;
+    cpu.load(&[0xad, 0x00, 0x02, 0xae, 0x00, 0x02], 0x8000);
+    cpu.step().unwrap();
+    cpu.step().unwrap();
+
+    assert_eq!(cpu.reg.a, 0x40);
+    assert_eq!(cpu.reg.x, 0x00);
+}
+
+#[test]
+fn words_straddling_two_peripherals() {
+    let mut cpu = common::cpu::CPU::default();
+    cpu.bus.attach(0x0000, 0x00ff, common::MockRAM::new(0x100)).unwrap();
+    cpu.bus.attach(0x0100, 0x01ff, common::MockRAM::new(0x100)).unwrap();
+
+    cpu.bus.write_u16(0x00ff, 0xbeef);
+    assert_eq!(cpu.bus.peek(0x00ff), 0xef);
+    assert_eq!(cpu.bus.peek(0x0100), 0xbe);
+    assert_eq!(cpu.bus.read_u16(0x00ff), 0xbeef);
+    assert_eq!(cpu.bus.peek_u16(0x00ff), 0xbeef);
+}
+
+#[test]
+fn words_wrap_around_the_address_space() {
+    let mut cpu = common::init_cpu();
+
+    cpu.bus.write_u16(0xffff, 0x1234);
+    assert_eq!(cpu.bus.peek(0xffff), 0x34);
+    assert_eq!(cpu.bus.peek(0x0000), 0x12);
+    assert_eq!(cpu.bus.read_u16(0xffff), 0x1234);
+}
+
+#[test]
+fn indirect_y_pointer_wraps_in_the_zero_page() {
+    let mut cpu = common::init_cpu();
+    cpu.bus.write(0x00ff, 0x00);
+    cpu.bus.write(0x0000, 0x02);
+    cpu.bus.write(0x0100, 0x04);
+    cpu.bus.write(0x0201, 0xaa);
+
+    // LDY #$01, LDA ($ff),Y
+    cpu.reg.pc = 0x8000;
+    cpu.load(&[0xa0, 0x01, 0xb1, 0xff], 0x8000);
+    cpu.step().unwrap();
+    cpu.step().unwrap();
+
+    assert_eq!(cpu.reg.a, 0xaa);
+}
+
+#[test]
+fn indirect_x_pointer_wraps_in_the_zero_page() {
+    let mut cpu = common::init_cpu();
+    cpu.bus.write(0x00ff, 0x00);
+    cpu.bus.write(0x0000, 0x03);
+    cpu.bus.write(0x0100, 0x04);
+    cpu.bus.write(0x0300, 0xbb);
+
+    // LDX #$0f, LDA ($f0,X)
+    cpu.reg.pc = 0x8000;
+    cpu.load(&[0xa2, 0x0f, 0xa1, 0xf0], 0x8000);
+    cpu.step().unwrap();
+    cpu.step().unwrap();
+
+    assert_eq!(cpu.reg.a, 0xbb);
+}
+
+#[test]
+fn overlaps_are_inclusive() {
+    let mut bus = common::bus::Bus::default();
+    bus.attach_ram(0x0000, 0x00ff).unwrap();
+
+    assert_eq!(
+        bus.attach_ram(0x00ff, 0x01ff),
+        Err(BusError::Overlap { range: (0x00ff, 0x01ff), other: (0x0000, 0x00ff) }));
+    assert!(bus.attach_ram(0x0100, 0x01ff).is_ok());
+    assert_eq!(bus.attach_ram(0x0300, 0x0200), Err(BusError::InvalidRange { lo: 0x0300, hi: 0x0200 }));
+}
+
+#[test]
+fn mirrored_ram() {
+    let mut bus = common::bus::Bus::default();
+    bus.attach_ram_region(Region::new(0x0000, 0x1fff).mirrored(0x0800)).unwrap();
+
+    bus.write(0x0012, 0xab);
+    assert_eq!(bus.peek(0x0812), 0xab);
+    assert_eq!(bus.peek(0x1012), 0xab);
+    assert_eq!(bus.peek(0x1812), 0xab);
+
+    bus.write(0x1fff, 0xcd);
+    assert_eq!(bus.peek(0x07ff), 0xcd);
+
+    assert_eq!(
+        bus.attach_ram_region(Region::new(0x2000, 0x2fff).mirrored(0x0300)),
+        Err(BusError::InvalidMirror { range: (0x2000, 0x2fff), size: 0x0300 }));
+}
+
+#[test]
+fn rom_overlay_shadows_ram() {
+    let mut bus = common::bus::Bus::default();
+    bus.attach_ram(0x0000, 0xffff).unwrap();
+    bus.write(0xfffc, 0x11);
+
+    let rom = bus.attach_rom_region(Region::new(0xfffc, 0xffff).with_priority(1), &[0x00, 0x80, 0x00, 0x90]).unwrap();
+    assert_eq!(bus.peek_u16(0xfffc), 0x8000);
+
+    // writes to the rom are ignored, the ram underneath is untouched
+    bus.write(0xfffc, 0x22);
+    assert_eq!(bus.peek(0xfffc), 0x00);
+
+    bus.detach(rom).unwrap();
+    assert_eq!(bus.peek(0xfffc), 0x11);
+    assert_eq!(bus.detach(rom), Err(BusError::UnknownHandle(rom)));
+}
+
+#[test]
+fn linear_scan_decodes_like_the_table() {
+    let mut bus = common::bus::Bus::default();
+    bus.attach_ram_region(Region::new(0x0000, 0x1fff).mirrored(0x0800)).unwrap();
+    bus.attach_ram(0x4000, 0xffff).unwrap();
+    bus.attach_rom_region(Region::new(0xfffc, 0xffff).with_priority(1), &[0; 4]).unwrap();
+    bus.attach_region(Region::new(0x7f00, 0x7f0f).with_priority(1), Status::default()).unwrap();
+    bus.attach(0x2000, 0x2000, Status::default()).unwrap();
+
+    for addr in 0..=0xffff {
+        assert_eq!(bus.get_peripheral_index_linear(addr), bus.get_peripheral_index(addr), "{addr:04x}");
+    }
+}
+
+#[test]
+fn detach_and_remap() {
+    let mut bus = common::bus::Bus::default();
+    let status = bus.attach(0x0200, 0x0200, Status::default()).unwrap();
+    let ram = bus.attach_ram(0x0000, 0x00ff).unwrap();
+
+    bus.write(0x0200, 0x01);
+    bus.remap(status, Region::new(0x0300, 0x0300)).unwrap();
+    assert_eq!(bus.peek(0x0200), 0x00);
+    assert_eq!(bus.peek(0x0300), 0x01);
+    assert_eq!(bus.region(status), Some(Region::new(0x0300, 0x0300)));
+
+    assert_eq!(
+        bus.remap(status, Region::new(0x00ff, 0x0100)),
+        Err(BusError::Overlap { range: (0x00ff, 0x0100), other: (0x0000, 0x00ff) }));
+    assert_eq!(
+        bus.remap(ram, Region::new(0x1000, 0x1fff)),
+        Err(BusError::SizeMismatch { expected: 0x1000, actual: 0x100 }));
+
+    bus.detach(ram).unwrap();
+    assert_eq!(bus.get_peripheral_index(0x0000), None);
+    assert_eq!(bus.peek(0x0300), 0x01);
+}
+
+#[test]
+fn mapper_switches_banks() {
+    let rom: Vec<u8> = (0..4).flat_map(|bank| vec![bank as u8; 0x1000]).collect();
+    let mut bus = common::bus::Bus::default();
+    bus.attach_ram(0x0000, 0xffff).unwrap();
+
+    let mapper = Mapper::new(&rom, 0x1000).unwrap()
+        .with_window(0x8000, 0)
+        .with_window(0x9000, 3)
+        .with_select(0x00fa)
+        .with_priority(1);
+    let handle = bus.attach_mapper(mapper).unwrap();
+
+    assert_eq!(bus.peek(0x8000), 0);
+    assert_eq!(bus.peek(0x9fff), 3);
+    assert_eq!(bus.peek(0x00fb), 3);
+
+    bus.write(0x00fa, 2);
+    bus.write(0x00fb, 5);
+    assert_eq!(bus.peek(0x8123), 2);
+    assert_eq!(bus.peek(0x9123), 1);
+    assert_eq!(bus.mapper(handle).unwrap().bank(1), Some(1));
+
+    // the banks are rom
+    bus.write(0x8000, 0xff);
+    assert_eq!(bus.peek(0x8000), 2);
+
+    assert_eq!(bus.remap(handle, Region::new(0xa000, 0xafff)), Err(BusError::CannotRemap(handle)));
+    bus.detach(handle).unwrap();
+    assert_eq!(bus.peek(0x8000), 0);
+    assert_eq!(bus.peek(0x00fa), 0);
+}
+
+#[test]
+fn mapper_validation() {
+    assert!(Mapper::new(&[0; 0x1800], 0x1000).is_err());
+    assert!(Mapper::new(&[0; 0x1800], 0x0c00).is_err());
+
+    let mut bus = common::bus::Bus::default();
+    let mapper = Mapper::new(&[0; 0x2000], 0x1000).unwrap()
+        .with_window(0x8000, 0)
+        .with_window(0x8800, 1);
+    assert_eq!(
+        bus.attach_mapper(mapper).err(),
+        Some(BusError::Overlap { range: (0x8800, 0x97ff), other: (0x8000, 0x8fff) }));
+}
+
+#[test]
+fn rom_writes_are_faults() {
+    let mut bus = common::bus::Bus::default();
+    bus.attach_ram(0x0000, 0x7fff).unwrap();
+    bus.attach_rom(0x8000, &[0; 0x8000]).unwrap();
+
+    bus.write(0x0010, 0x01);
+    assert_eq!(bus.take_fault(), None);
+
+    bus.write(0x8010, 0x01);
+    assert_eq!(bus.peek(0x8010), 0x00);
+    assert_eq!(bus.take_fault(), Some(0x8010));
+    assert_eq!(bus.take_fault(), None);
+
+    // loaders can still write to the rom
+    bus.poke(0x8010, 0x02);
+    assert_eq!(bus.peek(0x8010), 0x02);
+    assert_eq!(bus.take_fault(), None);
+}
+
+#[test]
+fn memory_region_is_a_side_effect_free_view() {
+    let mut cpu = init();
+    cpu.bus.write(0x0200, 0x81);
+    cpu.load(&[0xaa, 0xbb], 0xfffe);
+
+    assert_eq!(cpu.bus.get_memory_region((0x01ff, 0x0201)), [0x00, 0x81, 0x00]);
+    assert_eq!(cpu.bus.read(0x0200), 0x81);
+    assert_eq!(cpu.bus.get_memory_region((0x0200, 0x0200)), [0x00]);
+
+    let memory = cpu.bus.get_memory_region((0x0000, 0xffff));
+    assert_eq!(memory.len(), 0x10000);
+    assert_eq!(memory[0xfffe..], [0xaa, 0xbb]);
+    assert!(cpu.bus.get_memory_region((0x0001, 0x0000)).is_empty());
+}
@@ -13,7 +13,7 @@ impl MockRAM {
 }
 
 impl bus::Peripheral for MockRAM {
-    fn read(&self, addr: u16) -> u8 {
+    fn peek(&self, addr: u16) -> u8 {
         self.data[addr as usize]
     }
 
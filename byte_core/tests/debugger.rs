@@ -0,0 +1,255 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+mod common;
+
+use common::debugger::*;
+
+// LDX #$00
+// loop:
+//   INX
+//   STX $0200
+//   JMP loop
+const PROGRAM: &[u8] = &[0xa2, 0x00, 0xe8, 0x8e, 0x00, 0x02, 0x4c, 0x02, 0x80];
+
+fn init() -> common::cpu::CPU {
+    let mut cpu = common::init_cpu();
+    cpu.reg.pc = 0x8000;
+    cpu.load(PROGRAM, 0x8000);
+    cpu
+}
+
+#[test]
+fn breakpoint_stops_before_the_instruction() {
+    let mut cpu = init();
+    cpu.debugger.add_breakpoint(Breakpoint::new(0x8003));
+
+    assert_eq!(cpu.run_until_break(100).unwrap(), StopReason::Breakpoint(0x8003));
+    assert_eq!(cpu.reg.pc, 0x8003);
+    assert_eq!(cpu.reg.x, 1);
+    assert_eq!(cpu.bus.peek(0x0200), 0);
+
+    // resuming executes the instruction instead of stopping again
+    assert_eq!(cpu.run_until_break(100).unwrap(), StopReason::Breakpoint(0x8003));
+    assert_eq!(cpu.reg.x, 2);
+    assert_eq!(cpu.bus.peek(0x0200), 1);
+}
+
+#[test]
+fn conditional_breakpoint() {
+    let mut cpu = init();
+    cpu.debugger.add_breakpoint(
+        Breakpoint::new(0x8003).with_condition("X == $05 && A < 3".parse().unwrap()));
+
+    assert_eq!(cpu.run_until_break(100).unwrap(), StopReason::Breakpoint(0x8003));
+    assert_eq!(cpu.reg.x, 5);
+}
+
+#[test]
+fn breakpoint_hit_count() {
+    let mut cpu = init();
+    cpu.debugger.add_breakpoint(Breakpoint::new(0x8002).with_ignore(3));
+
+    assert_eq!(cpu.run_until_break(100).unwrap(), StopReason::Breakpoint(0x8002));
+    assert_eq!(cpu.reg.x, 3);
+    assert_eq!(cpu.debugger.breakpoint(0x8002).unwrap().hits, 4);
+}
+
+#[test]
+fn write_watchpoint_stops_after_the_instruction() {
+    let mut cpu = init();
+    cpu.debugger.add_watchpoint(
+        Watchpoint::new(0x0200, 0x02ff, Access::WRITE).with_condition("x >= 2".parse().unwrap()));
+
+    let reason = cpu.run_until_break(100).unwrap();
+    assert_eq!(reason, StopReason::Watchpoint { addr: 0x0200, access: Access::WRITE, pc: 0x8003 });
+    assert_eq!(cpu.reg.pc, 0x8006);
+    assert_eq!(cpu.bus.peek(0x0200), 2);
+}
+
+#[test]
+fn read_watchpoint_ignores_writes() {
+    let mut cpu = init();
+    cpu.debugger.add_watchpoint(Watchpoint::new(0x0200, 0x0200, Access::READ));
+
+    assert_eq!(cpu.run_until_break(100).unwrap(), StopReason::Limit);
+}
+
+#[test]
+fn read_watchpoint_ignores_instruction_fetches() {
+    let mut cpu = init();
+    cpu.debugger.add_watchpoint(Watchpoint::new(0x8000, 0x80ff, Access::READ));
+
+    assert_eq!(cpu.run_until_break(100).unwrap(), StopReason::Limit);
+    assert_eq!(cpu.debugger.watchpoints()[0].hits, 0);
+}
+
+#[test]
+fn interrupts_hit_watchpoints() {
+    let mut cpu = init();
+    cpu.load(&[0x00, 0x90], 0xfffe);
+    cpu.load(&[0x40], 0x9000); // RTI
+    cpu.debugger.add_watchpoint(Watchpoint::new(0x0100, 0x01ff, Access::WRITE));
+
+    let sp = cpu.reg.sp;
+    cpu.interrupt(common::cpu::Interrupt::IRQ);
+
+    // the handler hasn't run yet
+    let reason = cpu.debug_step().unwrap();
+    assert_eq!(reason, Some(StopReason::Watchpoint { addr: 0x0100 + sp as u16, access: Access::WRITE, pc: 0x9000 }));
+    assert_eq!(cpu.reg.pc, 0x9000);
+
+    assert_eq!(cpu.debug_step().unwrap(), None);
+    assert_eq!(cpu.reg.pc, 0x8000);
+}
+
+#[test]
+fn execute_watchpoint() {
+    let mut cpu = init();
+    cpu.debugger.add_watchpoint(Watchpoint::new(0x8006, 0x8008, Access::EXECUTE));
+
+    let reason = cpu.run_until_break(100).unwrap();
+    assert_eq!(reason, StopReason::Watchpoint { addr: 0x8006, access: Access::EXECUTE, pc: 0x8006 });
+}
+
+#[test]
+fn instruction_limit() {
+    let mut cpu = init();
+
+    assert_eq!(cpu.run_until_break(4).unwrap(), StopReason::Limit);
+    assert_eq!(cpu.reg.pc, 0x8002);
+}
+
+#[test]
+fn rom_write_fault() {
+    let mut cpu = common::cpu::CPU::default();
+    cpu.bus.attach_ram(0x0000, 0x7fff).unwrap();
+    cpu.bus.attach_rom(0x8000, &[0; 0x8000]).unwrap();
+
+    // LDX #$00, INX, STX $8100, JMP $8002
+    cpu.reg.pc = 0x8000;
+    cpu.load(&[0xa2, 0x00, 0xe8, 0x8e, 0x00, 0x81, 0x4c, 0x02, 0x80], 0x8000);
+
+    // faults are ignored unless asked for
+    assert_eq!(cpu.run_until_break(8).unwrap(), StopReason::Limit);
+    assert_eq!(cpu.bus.peek(0x8100), 0x00);
+
+    cpu.debugger.set_break_on_fault(true);
+    assert_eq!(cpu.run_until_break(8).unwrap(), StopReason::WriteFault { addr: 0x8100, pc: 0x8003 });
+    assert_eq!(cpu.reg.pc, 0x8006);
+    assert_eq!(cpu.bus.peek(0x8100), 0x00);
+}
+
+#[test]
+fn break_on_brk() {
+    let mut cpu = common::init_cpu();
+    cpu.bus.write_u16(0xfffe, 0x9000);
+
+    // INX, INX, BRK
+    cpu.reg.pc = 0x8000;
+    cpu.load(&[0xe8, 0xe8, 0x00], 0x8000);
+    cpu.debugger.set_break_on_brk(true);
+
+    assert_eq!(cpu.run_until_break(8).unwrap(), StopReason::Brk(0x8002));
+    assert_eq!(cpu.reg.x, 2);
+
+    // resuming executes it
+    cpu.debug_step().unwrap();
+    assert_eq!(cpu.reg.pc, 0x9000);
+}
+
+#[test]
+fn condition_parsing() {
+    assert!(Condition::parse("A == $40 && X > 3").is_ok());
+    assert!(Condition::parse("(a = %1010 || pc != 0x8000) && c == 1").is_ok());
+    assert_eq!(Condition::parse("A =="), Err(ConditionError::UnexpectedEnd));
+    assert_eq!(Condition::parse("A == $zz"), Err(ConditionError::InvalidNumber("$zz".into())));
+    assert_eq!(Condition::parse("A == 1 X"), Err(ConditionError::UnexpectedToken("X".into())));
+}
+
+// JSR $8010
+// INX
+// JMP $8004
+// ...
+// $8010: JSR $8020
+//        RTS
+// ...
+// $8020: INY
+//        RTS
+fn init_calls() -> common::cpu::CPU {
+    let mut cpu = common::init_cpu();
+    cpu.reg.pc = 0x8000;
+    cpu.reg.sp = 0xff;
+    cpu.load(&[0x20, 0x10, 0x80, 0xe8, 0x4c, 0x04, 0x80], 0x8000);
+    cpu.load(&[0x20, 0x20, 0x80, 0x60], 0x8010);
+    cpu.load(&[0xc8, 0x60], 0x8020);
+    cpu
+}
+
+#[test]
+fn step_into_tracks_calls() {
+    let mut cpu = init_calls();
+
+    cpu.set_goal(Goal::Step);
+    assert_eq!(cpu.run_until_break(100).unwrap(), StopReason::Goal);
+    assert_eq!(cpu.reg.pc, 0x8010);
+    assert_eq!(cpu.debugger.call_stack(), &[
+        Frame { caller: 0x8000, target: 0x8010, sp: 0xfd, interrupt: None },
+    ]);
+
+    cpu.set_goal(Goal::Step);
+    assert_eq!(cpu.run_until_break(100).unwrap(), StopReason::Goal);
+    assert_eq!(cpu.reg.pc, 0x8020);
+    assert_eq!(cpu.debugger.call_stack().len(), 2);
+}
+
+#[test]
+fn step_over_runs_the_whole_subroutine() {
+    let mut cpu = init_calls();
+
+    cpu.set_goal(Goal::StepOver);
+    assert_eq!(cpu.run_until_break(100).unwrap(), StopReason::Goal);
+    assert_eq!(cpu.reg.pc, 0x8003);
+    assert_eq!(cpu.reg.y, 1);
+    assert!(cpu.debugger.call_stack().is_empty());
+}
+
+#[test]
+fn step_out_returns_to_the_caller() {
+    let mut cpu = init_calls();
+    cpu.debugger.add_breakpoint(Breakpoint::new(0x8020));
+    assert_eq!(cpu.run_until_break(100).unwrap(), StopReason::Breakpoint(0x8020));
+
+    cpu.set_goal(Goal::StepOut);
+    assert_eq!(cpu.run_until_break(100).unwrap(), StopReason::Goal);
+    assert_eq!(cpu.reg.pc, 0x8013);
+    assert_eq!(cpu.debugger.call_stack().len(), 1);
+
+    cpu.set_goal(Goal::StepOut);
+    assert_eq!(cpu.run_until_break(100).unwrap(), StopReason::Goal);
+    assert_eq!(cpu.reg.pc, 0x8003);
+}
+
+#[test]
+fn run_to_address() {
+    let mut cpu = init_calls();
+
+    cpu.set_goal(Goal::RunTo(0x8004));
+    assert_eq!(cpu.run_until_break(100).unwrap(), StopReason::Goal);
+    assert_eq!(cpu.reg.pc, 0x8004);
+    assert_eq!(cpu.reg.x, 1);
+}
+
+#[test]
+fn interrupts_show_up_in_the_call_stack() {
+    let mut cpu = init_calls();
+    cpu.load(&[0x00, 0x90], 0xfffe);
+    cpu.load(&[0x40], 0x9000); // RTI
+
+    cpu.interrupt(common::cpu::Interrupt::IRQ);
+    assert_eq!(cpu.debugger.call_stack()[0].interrupt, Some(common::cpu::Interrupt::IRQ));
+
+    cpu.set_goal(Goal::StepOut);
+    assert_eq!(cpu.run_until_break(100).unwrap(), StopReason::Goal);
+    assert_eq!(cpu.reg.pc, 0x8000);
+    assert!(cpu.debugger.call_stack().is_empty());
+}
@@ -0,0 +1,49 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+mod common;
+
+use common::disasm::{find_start, Instruction};
+
+fn memory(code: &[u8], start: u16) -> Vec<u8> {
+    let mut memory = vec![0xff; 0x10000];
+    memory[start as usize..start as usize + code.len()].copy_from_slice(code);
+    memory
+}
+
+#[test]
+fn decode_and_format() {
+    let memory = memory(&[0xa9, 0x40, 0x8d, 0x00, 0x02, 0xb1, 0x10, 0xd0, 0xfe, 0x02], 0x8000);
+    let read = |addr: u16| memory[addr as usize];
+
+    let lines: Vec<String> = [0x8000, 0x8002, 0x8005, 0x8007, 0x8009]
+        .iter()
+        .map(|addr| Instruction::decode(*addr, read).to_string())
+        .collect();
+
+    assert_eq!(lines, ["LDA #$40", "STA $0200", "LDA ($10),Y", "BNE $8007", ".DB $02"]);
+    assert_eq!(Instruction::decode(0x8002, read).bytes(), &[0x8d, 0x00, 0x02]);
+    assert_eq!(Instruction::decode(0x8002, read).next(), 0x8005);
+}
+
+#[test]
+fn find_start_lands_on_the_anchor() {
+    // LDA #$01, STA $0200, INX, INX, JMP $8000
+    let memory = memory(&[0xa9, 0x01, 0x8d, 0x00, 0x02, 0xe8, 0xe8, 0x4c, 0x00, 0x80], 0x8000);
+    let read = |addr: u16| memory[addr as usize];
+
+    assert_eq!(find_start(0x8007, 2, read), 0x8005);
+    assert_eq!(find_start(0x8007, 4, read), 0x8000);
+    assert_eq!(find_start(0x8007, 0, read), 0x8007);
+}
+
+#[test]
+fn find_start_skips_over_data() {
+    // a string in front of the code, then LDX #$00, INX
+    let mut memory = memory(b"HI!\x02\x03", 0x8000);
+    memory[0x8005..0x8008].copy_from_slice(&[0xa2, 0x00, 0xe8]);
+    let read = |addr: u16| memory[addr as usize];
+
+    let start = find_start(0x8007, 1, read);
+    assert_eq!(start, 0x8005);
+    assert_eq!(Instruction::decode(start, read).next(), 0x8007);
+}
@@ -0,0 +1,123 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+mod common;
+
+use common::hook::{Hook, StepInfo};
+use common::trace::TraceLogger;
+use common::bus::Bus;
+use std::cell::RefCell;
+use std::io::Write;
+use std::rc::Rc;
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Before(u16, Option<u16>),
+    After(u16, u8),
+    Read(u16, u8),
+    Write(u16, u8),
+}
+
+struct Recorder(Rc<RefCell<Vec<Event>>>);
+
+impl Hook for Recorder {
+    fn before_instruction(&mut self, info: &StepInfo, _bus: &Bus) {
+        self.0.borrow_mut().push(Event::Before(info.pc, info.operand));
+    }
+
+    fn after_instruction(&mut self, info: &StepInfo, _bus: &Bus) {
+        self.0.borrow_mut().push(Event::After(info.pc, info.reg.a));
+    }
+
+    fn on_read(&mut self, addr: u16, byte: u8) {
+        self.0.borrow_mut().push(Event::Read(addr, byte));
+    }
+
+    fn on_write(&mut self, addr: u16, byte: u8) {
+        self.0.borrow_mut().push(Event::Write(addr, byte));
+    }
+}
+
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn hook_observes_instructions_and_memory() {
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut cpu = common::init_cpu();
+
+    // LDA #$40
+    // STA $0200
+    cpu.reg.pc = 0x8000;
+    cpu.load(&[0xa9, 0x40, 0x8d, 0x00, 0x02], 0x8000);
+    cpu.hook = Some(Box::new(Recorder(events.clone())));
+
+    cpu.step().unwrap();
+    cpu.step().unwrap();
+
+    assert_eq!(*events.borrow(), vec![
+        Event::Read(0x8000, 0xa9),
+        Event::Before(0x8000, Some(0x8001)),
+        Event::Read(0x8001, 0x40),
+        Event::After(0x8000, 0x40),
+        Event::Read(0x8002, 0x8d),
+        Event::Before(0x8002, Some(0x0200)),
+        Event::Read(0x8003, 0x00),
+        Event::Read(0x8004, 0x02),
+        Event::Write(0x0200, 0x40),
+        Event::After(0x8002, 0x40),
+    ]);
+}
+
+#[test]
+fn trace_logger_writes_nestest_lines() {
+    let buffer = SharedBuffer::default();
+    let mut cpu = common::init_cpu();
+
+    // LDX #$02
+    // LDA ($10,X)
+    // JMP $8000
+    cpu.reg.pc = 0x8000;
+    cpu.reg.sp = 0xfd;
+    cpu.load(&[0xa2, 0x02, 0xa1, 0x10, 0x4c, 0x00, 0x80], 0x8000);
+    cpu.load(&[0x00, 0x03], 0x0012);
+    cpu.load(&[0x5a], 0x0300);
+    cpu.hook = Some(Box::new(TraceLogger::new(buffer.clone())));
+
+    (0..3).for_each(|_| cpu.step().unwrap());
+
+    let trace = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+    assert_eq!(trace.lines().collect::<Vec<_>>(), vec![
+        "8000  A2 02     LDX #$02                        A:00 X:00 Y:00 P:00 SP:FD CYC:0",
+        "8002  A1 10     LDA ($10,X) @ 12 = 0300 = 5A    A:00 X:02 Y:00 P:00 SP:FD CYC:2",
+        "8004  4C 00 80  JMP $8000                       A:5A X:02 Y:00 P:00 SP:FD CYC:8",
+    ]);
+}
+
+#[test]
+fn hook_sees_the_indirect_jump_bug() {
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut cpu = common::init_cpu();
+
+    // JMP ($10FF), the high byte comes from $1000 rather than $1100
+    cpu.reg.pc = 0x8000;
+    cpu.load(&[0x6c, 0xff, 0x10], 0x8000);
+    cpu.load(&[0x34], 0x10ff);
+    cpu.load(&[0x12], 0x1000);
+    cpu.load(&[0x56], 0x1100);
+    cpu.hook = Some(Box::new(Recorder(events.clone())));
+
+    cpu.step().unwrap();
+
+    assert_eq!(cpu.reg.pc, 0x1234);
+    assert_eq!(events.borrow()[1], Event::Before(0x8000, Some(0x1234)));
+}
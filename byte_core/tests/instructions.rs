@@ -35,7 +35,7 @@ fn opcode_0x06_zeropage_asl() {
     let cpu = execute_nsteps(
         |cpu| cpu.bus.write(0xaa, 0b1010_1010), &[0x06, 0xaa, 0x00], 0x8000, 1);
 
-    assert_eq!(cpu.bus.read(0xaa), 0b0101_0100);
+    assert_eq!(cpu.bus.peek(0xaa), 0b0101_0100);
     assert!(cpu.reg.p.contains(Flags::CARRY));
     assert!(!cpu.reg.p.contains(Flags::NEGATIVE));
 }
@@ -215,7 +215,7 @@ fn opcode_0xc6_zeropage_dec() {
     let cpu = execute_nsteps(
         |cpu| cpu.bus.write(0xaa, 0xff), &[0xc6, 0xaa, 0x00], 0x8000, 1);
 
-    assert_eq!(cpu.bus.read(0x00aa), 0xfe);
+    assert_eq!(cpu.bus.peek(0x00aa), 0xfe);
 }
 
 #[test]
@@ -258,7 +258,7 @@ fn opcode_0xe6_zeropage_inc() {
     let cpu = execute_nsteps(
         |cpu| cpu.bus.write(0xaa, 0xfe), &[0xe6, 0xaa, 0x00], 0x8000, 1);
 
-    assert_eq!(cpu.bus.read(0x00aa), 0xff);
+    assert_eq!(cpu.bus.peek(0x00aa), 0xff);
 }
 
 #[test]
@@ -460,7 +460,7 @@ fn opcode_0x46_zeropage_lsr() {
     let cpu = execute_nsteps(
         |cpu| cpu.bus.write(0xde, 0xad), &[0x46, 0xde, 0x00], 0x8000, 1);
 
-    assert!(cpu.bus.read(0xde) == 0xadu8.wrapping_shr(1));
+    assert!(cpu.bus.peek(0xde) == 0xadu8.wrapping_shr(1));
     assert!(cpu.reg.p.contains(Flags::CARRY));
 }
 
@@ -580,7 +580,7 @@ fn opcode_0x85_zeropage_sta() {
     let cpu = execute_nsteps(
         |cpu| cpu.reg.a = 0xad, &[0x85, 0xde, 0x00], 0x8000, 1);
 
-    assert_eq!(cpu.bus.read(0xde), 0xad);
+    assert_eq!(cpu.bus.peek(0xde), 0xad);
 }
 
 #[test]
@@ -588,7 +588,7 @@ fn opcode_0x86_zeropage_stx() {
     let cpu = execute_nsteps(
         |cpu| cpu.reg.x = 0xef, &[0x86, 0xbe, 0x00], 0x8000, 1);
 
-    assert_eq!(cpu.bus.read(0xbe), 0xef);
+    assert_eq!(cpu.bus.peek(0xbe), 0xef);
 }
 
 #[test]
@@ -596,7 +596,7 @@ fn opcode_0x84_zeropage_sty() {
     let cpu = execute_nsteps(
         |cpu| cpu.reg.y = 0xed, &[0x84, 0xfe, 0x00], 0x8000, 1);
 
-    assert_eq!(cpu.bus.read(0xfe), 0xed);
+    assert_eq!(cpu.bus.peek(0xfe), 0xed);
 }
 
 #[test]
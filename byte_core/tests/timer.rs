@@ -0,0 +1,119 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+mod common;
+
+use common::bus::{timer::*, Peripheral, Timer};
+use std::{cell::RefCell, rc::Rc};
+
+fn start_t1(timer: &mut Timer, count: u16) {
+    let [lo, hi] = count.to_le_bytes();
+    timer.write(T1C_L, lo);
+    timer.write(T1C_H, hi);
+}
+
+#[test]
+fn one_shot_fires_once() {
+    let mut timer = Timer::default();
+    timer.write(IER, 0x80 | IRQ_T1);
+    start_t1(&mut timer, 10);
+
+    timer.clock(10);
+    assert_eq!(timer.peek(T1C_L), 0);
+    assert!(!timer.irq());
+
+    timer.clock(1);
+    assert!(timer.irq());
+    assert_eq!(timer.peek(IFR), 0x80 | IRQ_T1);
+
+    // acknowledged by reading the counter, the counter keeps going but
+    // doesn't fire again until it's rewritten
+    timer.read(T1C_L);
+    assert!(!timer.irq());
+    timer.clock(0x20000);
+    assert!(!timer.irq());
+}
+
+#[test]
+fn free_running_reloads_from_the_latch() {
+    let mut timer = Timer::default();
+    timer.write(ACR, 0x40);
+    start_t1(&mut timer, 4);
+
+    // n + 2 cycles per period
+    for _ in 0..3 {
+        timer.clock(5);
+        assert_eq!(timer.peek(IFR) & IRQ_T1, IRQ_T1);
+        assert_eq!(timer.peek(T1C_L), 0xff);
+        timer.write(IFR, IRQ_T1);
+
+        timer.clock(1);
+        assert_eq!(timer.peek(T1C_L), 4);
+        assert_eq!(timer.peek(IFR), 0);
+    }
+
+    // a new latch value is picked up on the next reload
+    timer.write(T1L_L, 9);
+    timer.write(T1L_H, 0);
+    timer.clock(6);
+    assert_eq!(timer.peek(T1C_L), 9);
+}
+
+#[test]
+fn timer_2_is_one_shot() {
+    let mut timer = Timer::default();
+    timer.write(ACR, 0x40);
+    timer.write(T2C_L, 0x00);
+    timer.write(T2C_H, 0x01);
+
+    // timer 1 is free-running as well, only look at timer 2's flag
+    timer.clock(0x101);
+    assert_eq!(timer.peek(IFR) & IRQ_T2, IRQ_T2);
+    timer.read(T2C_L);
+    timer.clock(0x10000);
+    assert_eq!(timer.peek(IFR) & IRQ_T2, 0);
+}
+
+#[test]
+fn interrupts_have_to_be_enabled() {
+    let mut timer = Timer::default();
+    start_t1(&mut timer, 0);
+    timer.clock(1);
+
+    assert_eq!(timer.peek(IFR), IRQ_T1);
+    assert!(!timer.irq());
+
+    timer.write(IER, 0x80 | IRQ_T1 | IRQ_T2);
+    assert_eq!(timer.peek(IER), 0x80 | IRQ_T1 | IRQ_T2);
+    assert!(timer.irq());
+
+    timer.write(IER, IRQ_T1);
+    assert_eq!(timer.peek(IER), 0x80 | IRQ_T2);
+    assert!(!timer.irq());
+}
+
+#[test]
+fn shared_timer_on_the_bus() {
+    let timer = Rc::new(RefCell::new(Timer::default()));
+    let mut cpu = common::cpu::CPU::default();
+    cpu.bus.attach(0x0000, 0x7fff, common::MockRAM::new(0x8000)).unwrap();
+    cpu.bus.attach(0x9000, 0x900f, timer.clone()).unwrap();
+    cpu.bus.attach(0xa000, 0xffff, common::MockRAM::new(0x6000)).unwrap();
+
+    // LDA #$20, STA $9004, LDA #$00, STA $9005, LDA $9004
+    cpu.reg.pc = 0xa000;
+    cpu.load(&[
+        0xa9, 0x20, 0x8d, 0x04, 0x90,
+        0xa9, 0x00, 0x8d, 0x05, 0x90,
+        0xad, 0x04, 0x90,
+    ], 0xa000);
+
+    for _ in 0..5 {
+        let cycle = cpu.cycle;
+        cpu.step().unwrap();
+        timer.borrow_mut().clock(cpu.cycle - cycle);
+    }
+
+    // the counter started counting down with the cycles of the store itself
+    assert_eq!(cpu.reg.a, 0x20 - 4);
+    assert_eq!(cpu.bus.peek(0x9006), 0x20);
+}
@@ -42,6 +42,19 @@ where
             }
         });
     }
+
+    // browsers can't write to files, saving is left out there
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&mut self, name: String, data: Vec<u8>) {
+        execute(async move {
+            let dialog = rfd::AsyncFileDialog::new().set_file_name(&name);
+            if let Some(file) = dialog.save_file().await {
+                if let Err(err) = std::fs::write(file.path(), data) {
+                    log::error!("failed to save {name}: {err}");
+                }
+            }
+        });
+    }
 }
 
 use std::future::Future;
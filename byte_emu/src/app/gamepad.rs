@@ -0,0 +1,68 @@
+use crate::emu::input::{Gamepads, PadButton};
+use gilrs::{Axis, Button};
+
+const BUTTONS: [(Button, PadButton); 10] = [
+    (Button::DPadUp, PadButton::Up),
+    (Button::DPadDown, PadButton::Down),
+    (Button::DPadLeft, PadButton::Left),
+    (Button::DPadRight, PadButton::Right),
+    (Button::South, PadButton::South),
+    (Button::East, PadButton::East),
+    (Button::North, PadButton::North),
+    (Button::West, PadButton::West),
+    (Button::Start, PadButton::Start),
+    (Button::Select, PadButton::Select),
+];
+
+// how far the left stick has to be pushed to count as the d-pad
+const STICK_THRESHOLD: f32 = 0.5;
+
+pub struct Gilrs(gilrs::Gilrs);
+
+impl Gilrs {
+    pub fn new() -> Option<Self> {
+        match gilrs::Gilrs::new() {
+            Ok(gilrs) => Some(Self(gilrs)),
+            Err(err) => {
+                log::error!("failed to open the gamepads: {err}");
+                None
+            }
+        }
+    }
+}
+
+impl Gamepads for Gilrs {
+    fn poll(&mut self) -> Vec<Vec<PadButton>> {
+        // gilrs only updates the state of the gamepads while handling events
+        while self.0.next_event().is_some() {}
+
+        self.0
+            .gamepads()
+            .map(|(_, gamepad)| {
+                let mut pressed = BUTTONS
+                    .iter()
+                    .filter(|(button, _)| gamepad.is_pressed(*button))
+                    .map(|(_, button)| *button)
+                    .collect::<Vec<_>>();
+
+                let x = gamepad.value(Axis::LeftStickX);
+                let y = gamepad.value(Axis::LeftStickY);
+                #[rustfmt::skip]
+                let stick = [
+                    (x >  STICK_THRESHOLD, PadButton::Right),
+                    (x < -STICK_THRESHOLD, PadButton::Left),
+                    (y >  STICK_THRESHOLD, PadButton::Up),
+                    (y < -STICK_THRESHOLD, PadButton::Down),
+                ];
+                pressed.extend(
+                    stick
+                        .iter()
+                        .filter(|(on, _)| *on)
+                        .map(|(_, button)| *button),
+                );
+
+                pressed
+            })
+            .collect()
+    }
+}
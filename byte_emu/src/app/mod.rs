@@ -1,33 +1,58 @@
 mod file_processor;
+#[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+mod gamepad;
 mod ui;
 
-use self::ui::code_editor::Theme as CodeEditorTheme;
+use self::ui::{code_editor::Theme as CodeEditorTheme, memory_monitor::MemoryMonitor};
 use crate::{
-    emu::core::{ByteEmu, ByteInputState},
+    emu::{
+        core::{ByteEmu, ByteInputState},
+        input::{self, Gamepads, KeyBindings, PLAYERS},
+        movie::Movie,
+        symbols::Symbols,
+    },
     DEFAULT_BINARY, DEFAULT_SOURCE,
 };
 use file_processor::FileProcesser;
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum FileProcesserMessage {
     BinaryFile((String, Vec<u8>)),
     SourceFile((String, Vec<u8>)),
+    SymbolFile((String, Vec<u8>)),
+    MovieFile((String, Vec<u8>)),
 }
 
-// `State` that we would like to persist (serialize).
+// `State` that we would like to persist (serialize). fields missing from
+// an older save fall back to their defaults instead of discarding it all.
 #[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
 pub struct State {
     // TODO: this is getting out of hand
     text: String,
+    // the first address the memory monitor shows and how many bytes
     memory_window_range: (u16, u16),
     memory_window_range_str: (String, String),
-    memory_window_text_area: String,
+    memory_search: String,
+    memory_search_string: bool,
+    memory_bookmarks: Vec<(u16, String)>,
     code_editor_theme: CodeEditorTheme,
+    run_to_addr: String,
+    disassembly_follow_pc: bool,
+    disassembly_addr: u16,
+    disassembly_addr_str: String,
+    // empty for a random seed
+    seed: String,
+    key_bindings: KeyBindings,
 
     is_about_open: bool,
     is_code_editor_open: bool,
+    is_disassembly_open: bool,
     is_emu_controls_open: bool,
+    is_key_bindings_open: bool,
     is_memory_monitor_open: bool,
+    is_terminal_open: bool,
 
     file_system: vfs::MemoryFS,
 }
@@ -36,7 +61,18 @@ pub struct ByteEmuApp {
     emu: ByteEmu,
     file_processer: FileProcesser<FileProcesserMessage>,
     state: State,
+    symbols: Symbols,
+    memory_monitor: MemoryMonitor,
     texture: egui::TextureHandle,
+    // what the program wrote to the terminal port
+    terminal: String,
+    // the movie recorded last, to replay it
+    last_movie: Option<Movie>,
+    gamepads: Option<Box<dyn Gamepads>>,
+    // the button waiting for a key to be bound to it
+    binding: Option<(usize, ByteInputState)>,
+    is_rewinding: bool,
+    is_scrubbing: bool,
 }
 
 impl Default for State {
@@ -45,36 +81,72 @@ impl Default for State {
             text: DEFAULT_SOURCE.to_string(),
             memory_window_range: (0, 0x100),
             memory_window_range_str: ("0x0000".into(), "0x100".into()),
-            memory_window_text_area: String::new(),
+            memory_search: String::new(),
+            memory_search_string: false,
+            memory_bookmarks: Vec::new(),
             code_editor_theme: CodeEditorTheme::Default,
+            run_to_addr: "0x8000".into(),
+            disassembly_follow_pc: true,
+            disassembly_addr: 0x8000,
+            disassembly_addr_str: "0x8000".into(),
+            seed: String::new(),
+            key_bindings: KeyBindings::default(),
 
             is_about_open: true,
             is_code_editor_open: true,
+            is_disassembly_open: false,
             is_emu_controls_open: false,
+            is_key_bindings_open: false,
             is_memory_monitor_open: false,
+            is_terminal_open: false,
 
             file_system: vfs::MemoryFS::new(),
         }
     }
 }
 
+#[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+fn gamepads() -> Option<Box<dyn Gamepads>> {
+    gamepad::Gilrs::new().map(|gilrs| Box::new(gilrs) as Box<dyn Gamepads>)
+}
+
+#[cfg(not(all(feature = "gamepad", not(target_arch = "wasm32"))))]
+fn gamepads() -> Option<Box<dyn Gamepads>> {
+    None
+}
+
 impl eframe::App for ByteEmuApp {
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, eframe::APP_KEY, &self.state);
     }
 
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
-        let mut input_state = ByteInputState::empty();
+        let mut input_state = [ByteInputState::empty(); PLAYERS];
+        if let Some(gamepads) = &mut self.gamepads {
+            let pressed = input::gamepad_state(gamepads.as_mut());
+            for (state, pressed) in input_state.iter_mut().zip(pressed) {
+                state.insert(pressed);
+            }
+        }
 
         self.show_menu_bar(ctx);
         self.show_code_editor(ctx);
+        self.show_disassembly(ctx);
         self.show_emu_controls(ctx);
+        self.show_key_bindings(ctx);
         self.show_memory_monitor(ctx);
+        self.show_terminal(ctx);
         self.show_about(ctx);
         self.show_byte_console(ctx, &mut input_state);
 
         self.process_files();
-        self.emu.step(input_state);
+        // movies can't go back in time
+        if self.is_rewinding && self.emu.movie_state().is_none() {
+            self.emu.rewind();
+        } else if !self.is_scrubbing {
+            self.emu.step(input_state);
+        }
+        self.update_terminal();
 
         // TODO: this might cause some problems when
         // `State` (specifically `file_system`) gets too big
@@ -88,18 +160,31 @@ impl eframe::App for ByteEmuApp {
 }
 
 impl ByteEmuApp {
-    pub fn new(cc: &eframe::CreationContext<'_>, program: Option<(Vec<u8>, u16)>) -> Self {
+    // `seed` overrides the one saved in the state
+    pub fn new(
+        cc: &eframe::CreationContext<'_>,
+        program: Option<(Vec<u8>, u16)>,
+        seed: Option<u32>,
+    ) -> Self {
         cc.egui_ctx.set_visuals(egui::Visuals::dark());
 
         let mut app = Self {
             emu: ByteEmu::default(),
             file_processer: FileProcesser::new(),
             state: State::default(),
+            symbols: Symbols::default(),
+            memory_monitor: MemoryMonitor::default(),
             texture: cc.egui_ctx.load_texture(
                 "framebuffer",
                 egui::ColorImage::new([64, 64], egui::Color32::BLACK),
                 Default::default(),
             ),
+            terminal: String::new(),
+            last_movie: None,
+            gamepads: gamepads(),
+            binding: None,
+            is_rewinding: false,
+            is_scrubbing: false,
         };
 
         if let Some(storage) = cc.storage {
@@ -108,6 +193,10 @@ impl ByteEmuApp {
             }
         }
 
+        if let Some(seed) = seed.or_else(|| app.state.seed.trim().parse().ok()) {
+            app.emu.set_seed(seed);
+        }
+
         match program {
             Some((program, start)) => app.emu.load_program(&program, start),
             None => app.emu.load_program(DEFAULT_BINARY, 0x0000),
@@ -125,10 +214,23 @@ impl ByteEmuApp {
                     // load the program
                     // and then issue a RST interrupt
                     self.emu.load_program(data, 0x0000);
+                    self.terminal.clear();
                 }
                 FileProcesserMessage::SourceFile((_, data)) => {
                     self.state.text = String::from_utf8_lossy(data).to_string()
                 }
+                FileProcesserMessage::SymbolFile((_, data)) => {
+                    self.symbols = Symbols::parse(&String::from_utf8_lossy(data))
+                }
+                FileProcesserMessage::MovieFile((name, data)) => match Movie::from_bytes(data) {
+                    Ok(movie) => self.play_movie(movie),
+                    Err(err) => log::error!("failed to load {name}: {err}"),
+                },
             });
     }
+
+    fn play_movie(&mut self, movie: Movie) {
+        self.emu.play_movie(movie);
+        self.terminal.clear();
+    }
 }
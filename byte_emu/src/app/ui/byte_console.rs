@@ -1,4 +1,7 @@
-use crate::{app::ByteEmuApp, emu::core::ByteInputState};
+use crate::{
+    app::ByteEmuApp,
+    emu::{core::ByteInputState, input::PLAYERS},
+};
 use egui::{load::SizedTexture, Color32, ColorImage};
 
 const M: f32 = 14.0; // margin
@@ -7,10 +10,17 @@ const B: f32 = 20.0; // width
 const S: f32 = 320.0; // screen size
 const K: f32 = S - 2.0 * M - 4.0 * A - B - B / 4.0; // uhh
 
+const REWIND_KEY: egui::Key = egui::Key::Backspace;
+
 impl ByteEmuApp {
-    pub fn show_byte_console(&mut self, ctx: &egui::Context, input_state: &mut ByteInputState) {
+    pub fn show_byte_console(
+        &mut self,
+        ctx: &egui::Context,
+        input_state: &mut [ByteInputState; PLAYERS],
+    ) {
         let framebuffer = self.framebuffer();
         self.texture.set(framebuffer, egui::TextureOptions::NEAREST);
+        self.is_rewinding = false;
 
         egui::Window::new("byte console")
             .resizable(false)
@@ -24,11 +34,18 @@ impl ByteEmuApp {
                         .filter(|l| l.order == egui::layers::Order::Middle)
                         .last()
                 });
-                if Some(current_layer) == top_middle_layer {
-                    input_state.insert(ctx.input(|i| i.keys_down.clone()).into());
+                // a key that's about to be bound doesn't press anything
+                if Some(current_layer) == top_middle_layer && self.binding.is_none() {
+                    let keys_down = ctx.input(|i| i.keys_down.clone());
+                    let pressed = self.state.key_bindings.state(&keys_down);
+                    for (state, pressed) in input_state.iter_mut().zip(pressed) {
+                        state.insert(pressed);
+                    }
+                    self.is_rewinding = ctx.input(|i| i.key_down(REWIND_KEY));
                 }
 
-                self.ui_byte_console(ui, input_state);
+                // the buttons on screen belong to player 1
+                self.ui_byte_console(ui, &mut input_state[0]);
             });
     }
 
@@ -68,9 +85,9 @@ impl ByteEmuApp {
     }
 
     fn framebuffer(&mut self) -> ColorImage {
-        let pixels = self
-            .emu
-            .framebuffer()
+        let frame = self.emu.framebuffer();
+        let pixels = frame
+            .pixels
             .iter()
             .map(|c| {
                 let [r, g, b, a] = c.to_be_bytes();
@@ -79,7 +96,7 @@ impl ByteEmuApp {
             .collect::<Vec<Color32>>();
 
         ColorImage {
-            size: [64, 64],
+            size: [frame.width, frame.height],
             pixels,
         }
     }
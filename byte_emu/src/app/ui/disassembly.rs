@@ -0,0 +1,187 @@
+use crate::{app::ByteEmuApp, emu::parse_addr};
+use byte_common::opcode::AddressingMode;
+use byte_core::{
+    debugger::Goal,
+    disasm::{self, Instruction},
+};
+use egui::{Color32, Label, RichText, Sense};
+
+const LINES: usize = 24;
+// instructions shown above the pc while following it
+const LINES_ABOVE_PC: usize = 8;
+
+impl ByteEmuApp {
+    pub fn show_disassembly(&mut self, ctx: &egui::Context) {
+        let mut open = self.state.is_disassembly_open;
+        egui::Window::new("Disassembly")
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                self.ui_disassembly(ui);
+            });
+        self.state.is_disassembly_open = open;
+    }
+
+    fn ui_disassembly(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.state.disassembly_follow_pc, "follow pc");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.state.disassembly_addr_str)
+                    .desired_width(80.0),
+            );
+
+            // labels from the symbol file work as well
+            let addr = self
+                .symbols
+                .addr(self.state.disassembly_addr_str.trim())
+                .or_else(|| parse_addr(&self.state.disassembly_addr_str));
+            if ui
+                .add_enabled(addr.is_some(), egui::Button::new("pin"))
+                .clicked()
+            {
+                if let Some(addr) = addr {
+                    self.pin_disassembly(addr);
+                }
+            }
+
+            if ui.button("▲").clicked() {
+                self.scroll_disassembly(-1);
+            }
+            if ui.button("▼").clicked() {
+                self.scroll_disassembly(1);
+            }
+        });
+        ui.separator();
+
+        let response = ui
+            .scope(|ui| {
+                ui.style_mut().override_font_id = Some(egui::FontId::monospace(12.0));
+                self.ui_disassembly_lines(ui);
+            })
+            .response;
+
+        if response.hovered() {
+            let delta = ui.input(|i| i.raw_scroll_delta.y);
+            if delta != 0.0 {
+                self.scroll_disassembly(if delta > 0.0 { -1 } else { 1 });
+            }
+        }
+    }
+
+    fn ui_disassembly_lines(&mut self, ui: &mut egui::Ui) {
+        let pc = self.emu.pc();
+        let mut addr = self.disassembly_top();
+
+        for _ in 0..LINES {
+            let instruction = Instruction::decode(addr, |addr| self.emu.peek(addr));
+
+            if let Some(label) = self.symbols.label(addr) {
+                ui.label(
+                    RichText::new(format!("{label}:")).color(Color32::from_rgb(100, 149, 237)),
+                );
+            }
+
+            let marker = match self.emu.debugger().breakpoint(addr) {
+                Some(bp) if bp.enabled => "●",
+                Some(_) => "○",
+                None => " ",
+            };
+            let bytes = instruction
+                .bytes()
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let text = format!(
+                "{marker} {addr:04x}  {bytes:<8}  {}",
+                self.format_instruction(&instruction)
+            );
+
+            let text = if addr == pc {
+                RichText::new(text)
+                    .color(Color32::BLACK)
+                    .background_color(Color32::from_rgb(238, 238, 119))
+            } else {
+                RichText::new(text).color(Color32::LIGHT_GRAY)
+            };
+
+            let response = ui
+                .add(Label::new(text).sense(Sense::click()))
+                .on_hover_text("click to toggle a breakpoint");
+            if response.clicked() {
+                self.emu.debugger().toggle_breakpoint(addr);
+            }
+            response.context_menu(|ui| {
+                if ui.button("run to here").clicked() {
+                    self.emu.run_until(Goal::RunTo(addr));
+                    ui.close_menu();
+                }
+                if ui.button("pin here").clicked() {
+                    self.pin_disassembly(addr);
+                    ui.close_menu();
+                }
+            });
+
+            addr = instruction.next();
+        }
+    }
+
+    // address of the first line, re-synced with the instruction stream
+    // so that data in front of the pc doesn't throw the listing off
+    fn disassembly_top(&self) -> u16 {
+        match self.state.disassembly_follow_pc {
+            true => disasm::find_start(self.emu.pc(), LINES_ABOVE_PC, |addr| self.emu.peek(addr)),
+            false => self.state.disassembly_addr,
+        }
+    }
+
+    fn pin_disassembly(&mut self, addr: u16) {
+        self.state.disassembly_follow_pc = false;
+        self.state.disassembly_addr = addr;
+        self.state.disassembly_addr_str = format!("0x{addr:04x}");
+    }
+
+    fn scroll_disassembly(&mut self, lines: i32) {
+        let top = self.disassembly_top();
+        let top = match lines < 0 {
+            true => disasm::find_start(top, lines.unsigned_abs() as usize, |addr| {
+                self.emu.peek(addr)
+            }),
+            false => (0..lines).fold(top, |addr, _| {
+                Instruction::decode(addr, |addr| self.emu.peek(addr)).next()
+            }),
+        };
+
+        self.pin_disassembly(top);
+    }
+
+    // replaces the operand with a label if the symbol file has one for it
+    fn format_instruction(&self, instruction: &Instruction) -> String {
+        let text = instruction.to_string();
+        let Some(opcode) = instruction.opcode else {
+            return text;
+        };
+
+        use AddressingMode::*;
+        let (target, hex) = match opcode.mode {
+            Implied | Accumulator | Immediate => return text,
+            Relative => {
+                let target = instruction.branch_target();
+                (target, format!("${target:04X}"))
+            }
+            ZeroPage | ZeroPageX | ZeroPageY | IndirectX | IndirectY => {
+                let target = instruction.operand();
+                (target, format!("${target:02X}"))
+            }
+            Absolute | AbsoluteX | AbsoluteY | Indirect => {
+                let target = instruction.operand();
+                (target, format!("${target:04X}"))
+            }
+        };
+
+        match self.symbols.label(target) {
+            Some(label) => text.replacen(&hex, label, 1),
+            None => text,
+        }
+    }
+}
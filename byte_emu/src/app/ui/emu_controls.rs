@@ -1,4 +1,20 @@
-use crate::app::ByteEmuApp;
+use crate::{
+    app::ByteEmuApp,
+    emu::{parse_addr, rand},
+};
+use byte_core::{cpu::Flags, debugger::Goal};
+use egui::{Color32, DragValue, RichText};
+
+const FLAGS: [(&str, Flags); 8] = [
+    ("N", Flags::NEGATIVE),
+    ("V", Flags::OVERFLOW),
+    ("-", Flags::UNUSED),
+    ("B", Flags::BREAK),
+    ("D", Flags::DECIMAL),
+    ("I", Flags::INTERRUPT),
+    ("Z", Flags::ZERO),
+    ("C", Flags::CARRY),
+];
 
 impl ByteEmuApp {
     pub fn show_emu_controls(&mut self, ctx: &egui::Context) {
@@ -6,8 +22,200 @@ impl ByteEmuApp {
         egui::Window::new("Emulator Controls")
             .open(&mut open)
             .show(ctx, |ui| {
-                ui.label("TODO");
+                self.ui_debugger_buttons(ui);
+                ui.separator();
+                self.ui_registers(ui);
+                ui.separator();
+                self.ui_call_stack(ui);
+                ui.separator();
+                self.ui_memory_map(ui);
+                ui.separator();
+                self.ui_seed(ui);
+                ui.separator();
+                self.ui_rewind_timeline(ui);
             });
         self.state.is_emu_controls_open = open;
     }
+
+    fn ui_debugger_buttons(&mut self, ui: &mut egui::Ui) {
+        let paused = self.emu.is_paused();
+
+        ui.horizontal(|ui| {
+            let status = match (paused, self.emu.stop_reason()) {
+                (false, _) => "running".to_string(),
+                (true, None) => "paused".to_string(),
+                (true, Some(reason)) => format!("stopped: {reason:x?}"),
+            };
+            ui.label(status);
+        });
+
+        ui.horizontal(|ui| {
+            if paused {
+                if ui.button("resume").clicked() {
+                    self.emu.resume();
+                }
+            } else if ui.button("pause").clicked() {
+                self.emu.pause();
+            }
+
+            ui.add_enabled_ui(paused, |ui| {
+                if ui.button("step").clicked() {
+                    self.emu.run_until(Goal::Step);
+                }
+                if ui.button("step over").clicked() {
+                    self.emu.run_until(Goal::StepOver);
+                }
+                ui.add_enabled_ui(!self.emu.call_stack().is_empty(), |ui| {
+                    if ui.button("step out").clicked() {
+                        self.emu.run_until(Goal::StepOut);
+                    }
+                });
+                if ui.button("frame").clicked() {
+                    self.emu.frame_advance();
+                }
+            });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("run to:");
+            ui.add(egui::TextEdit::singleline(&mut self.state.run_to_addr).desired_width(60.0));
+
+            let addr = parse_addr(&self.state.run_to_addr);
+            if ui
+                .add_enabled(addr.is_some(), egui::Button::new("run"))
+                .clicked()
+            {
+                if let Some(addr) = addr {
+                    self.emu.run_until(Goal::RunTo(addr));
+                }
+            }
+        });
+    }
+
+    fn ui_registers(&mut self, ui: &mut egui::Ui) {
+        // registers only make sense to edit while the emulation is stopped
+        let paused = self.emu.is_paused();
+        let cycle = self.emu.cycle();
+        let reg = self.emu.registers();
+
+        ui.add_enabled_ui(paused, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("A");
+                ui.add(DragValue::new(&mut reg.a).hexadecimal(2, false, true));
+                ui.label("X");
+                ui.add(DragValue::new(&mut reg.x).hexadecimal(2, false, true));
+                ui.label("Y");
+                ui.add(DragValue::new(&mut reg.y).hexadecimal(2, false, true));
+            });
+            ui.horizontal(|ui| {
+                ui.label("SP");
+                ui.add(DragValue::new(&mut reg.sp).hexadecimal(2, false, true));
+                ui.label("PC");
+                ui.add(DragValue::new(&mut reg.pc).hexadecimal(4, false, true));
+            });
+            ui.horizontal(|ui| {
+                for (name, flag) in FLAGS {
+                    let mut set = reg.p.contains(flag);
+                    if ui.checkbox(&mut set, name).changed() {
+                        reg.p.set(flag, set);
+                    }
+                }
+            });
+        });
+        ui.label(format!("cycles: {cycle}"));
+    }
+
+    fn ui_call_stack(&mut self, ui: &mut egui::Ui) {
+        ui.label("call stack:");
+
+        let call_stack = self.emu.call_stack();
+        if call_stack.is_empty() {
+            ui.label(RichText::new("empty").color(Color32::GRAY));
+            return;
+        }
+
+        egui::ScrollArea::vertical()
+            .max_height(120.0)
+            .show(ui, |ui| {
+                ui.style_mut().override_font_id = Some(egui::FontId::monospace(12.0));
+
+                for frame in call_stack.iter().rev() {
+                    let text = match frame.interrupt {
+                        Some(interrupt) => format!(
+                            "${:04x} <{interrupt:?}> from ${:04x}",
+                            frame.target, frame.caller
+                        ),
+                        None => format!("${:04x} from ${:04x}", frame.target, frame.caller),
+                    };
+                    ui.label(text);
+                }
+            });
+    }
+
+    fn ui_memory_map(&mut self, ui: &mut egui::Ui) {
+        let map = self.emu.memory_map();
+
+        ui.label("memory map:");
+        ui.style_mut().override_font_id = Some(egui::FontId::monospace(12.0));
+        ui.label(format!("ram ${:04x}-${:04x}", map.ram.0, map.ram.1));
+        ui.label(format!("i/o ${:04x}-${:04x}", map.io.0, map.io.1));
+        if let Some((lo, hi)) = map.rom {
+            ui.label(format!("rom ${lo:04x}-${hi:04x}"));
+        }
+        ui.style_mut().override_font_id = None;
+
+        let debugger = self.emu.debugger();
+        let mut enabled = debugger.breaks_on_fault();
+        if ui.checkbox(&mut enabled, "break on rom writes").changed() {
+            debugger.set_break_on_fault(enabled);
+        }
+    }
+
+    // applying reseeds the generator right away, programs loaded later
+    // start from the same seed. an empty field picks a random one
+    fn ui_seed(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("rng seed:");
+            ui.add(egui::TextEdit::singleline(&mut self.state.seed).desired_width(80.0));
+
+            let text = self.state.seed.trim();
+            let seed = text.parse::<u32>().ok();
+            if ui
+                .add_enabled(
+                    text.is_empty() || seed.is_some(),
+                    egui::Button::new("apply"),
+                )
+                .clicked()
+            {
+                self.emu
+                    .set_seed(seed.unwrap_or_else(|| rand::random_seed() as u32));
+            }
+        });
+        ui.label(format!("current seed: {}", self.emu.seed()));
+    }
+
+    fn ui_rewind_timeline(&mut self, ui: &mut egui::Ui) {
+        let len = self.emu.rewind_len();
+        let mut position = self.emu.rewind_position();
+
+        ui.label("timeline:");
+        // movies can't go back in time
+        let enabled = len > 1 && self.emu.movie_state().is_none();
+        ui.add_enabled_ui(enabled, |ui| {
+            let response = ui
+                .add(egui::Slider::new(&mut position, 0..=len.saturating_sub(1)).show_value(false));
+
+            // stepping on throws away the frames after the new position,
+            // so seeking pauses until the emulation is resumed
+            if response.changed() {
+                self.emu.seek(position);
+                self.emu.pause();
+            }
+            self.is_scrubbing = response.dragged();
+        });
+        ui.label(format!(
+            "frame {} / {len} (hold backspace to rewind)",
+            position + 1
+        ));
+    }
 }
@@ -0,0 +1,86 @@
+use crate::{
+    app::ByteEmuApp,
+    emu::input::{KeyBindings, BUTTONS, PLAYERS},
+};
+use egui::{Color32, RichText};
+
+impl ByteEmuApp {
+    pub fn show_key_bindings(&mut self, ctx: &egui::Context) {
+        let mut open = self.state.is_key_bindings_open;
+        egui::Window::new("Key Bindings")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                self.ui_key_bindings(ui);
+            });
+        self.state.is_key_bindings_open = open;
+
+        if !open {
+            self.binding = None;
+        }
+        self.capture_binding(ctx);
+    }
+
+    // the next key that's pressed gets bound, escape cancels
+    fn capture_binding(&mut self, ctx: &egui::Context) {
+        let Some((player, button)) = self.binding else {
+            return;
+        };
+
+        let key = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Key {
+                    key,
+                    pressed: true,
+                    repeat: false,
+                    ..
+                } => Some(*key),
+                _ => None,
+            })
+        });
+        match key {
+            Some(egui::Key::Escape) => self.binding = None,
+            Some(key) => {
+                self.state.key_bindings.bind(player, button, key);
+                self.binding = None;
+            }
+            None => (),
+        }
+    }
+
+    fn ui_key_bindings(&mut self, ui: &mut egui::Ui) {
+        for player in 0..PLAYERS {
+            ui.label(format!("player {}:", player + 1));
+            egui::Grid::new(("key_bindings", player))
+                .striped(true)
+                .show(ui, |ui| {
+                    for (name, button) in BUTTONS {
+                        ui.label(name);
+                        ui.horizontal(|ui| {
+                            let keys = self.state.key_bindings.keys(player, button).to_vec();
+                            for key in keys {
+                                if ui.button(key.name()).on_hover_text("remove").clicked() {
+                                    self.state.key_bindings.unbind(player, button, key);
+                                }
+                            }
+
+                            if self.binding == Some((player, button)) {
+                                ui.label(RichText::new("press a key").color(Color32::GRAY));
+                            } else if ui.button("+").clicked() {
+                                self.binding = Some((player, button));
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+            ui.separator();
+        }
+
+        if self.gamepads.is_none() {
+            ui.label(RichText::new("no gamepad support in this build").color(Color32::GRAY));
+        }
+        if ui.button("reset to defaults").clicked() {
+            self.state.key_bindings = KeyBindings::default();
+            self.binding = None;
+        }
+    }
+}
@@ -1,6 +1,60 @@
-use egui::{Color32, Label, RichText, ScrollArea};
+use egui::{Color32, Label, Rect, RichText, ScrollArea, Sense};
 
-use crate::app::ByteEmuApp;
+use crate::{app::ByteEmuApp, emu::parse_addr};
+
+const ADDR_COLOR: Color32 = Color32::from_rgb(100, 149, 237);
+const CHANGED_COLOR: Color32 = Color32::from_rgb(255, 119, 119);
+const CURSOR_COLOR: Color32 = Color32::from_rgb(0, 136, 255);
+const MATCH_COLOR: Color32 = Color32::from_rgb(0, 85, 51);
+
+#[derive(Clone, Copy)]
+struct Cursor {
+    addr: u16,
+    // editing the ascii column instead of the hex one
+    ascii: bool,
+}
+
+// monitor state that doesn't need to survive a restart
+#[derive(Default)]
+pub struct MemoryMonitor {
+    cursor: Option<Cursor>,
+    // high nibble typed into the hex column, waiting for the low one
+    nibble: Option<u8>,
+    // memory before and after the last frame/step, used to highlight changes
+    previous: Vec<u8>,
+    current: Vec<u8>,
+    cycle: u64,
+    search_match: Option<(u16, usize)>,
+    search_status: String,
+}
+
+impl MemoryMonitor {
+    fn update(&mut self, memory: Vec<u8>, cycle: u64) {
+        if self.current.is_empty() {
+            self.current = memory;
+        } else if self.cycle != cycle {
+            self.previous = std::mem::replace(&mut self.current, memory);
+        }
+        self.cycle = cycle;
+    }
+
+    fn changed(&self, addr: u16) -> bool {
+        match (
+            self.previous.get(addr as usize),
+            self.current.get(addr as usize),
+        ) {
+            (Some(previous), Some(current)) => previous != current,
+            _ => false,
+        }
+    }
+
+    fn matched(&self, addr: u16) -> bool {
+        match self.search_match {
+            Some((start, len)) => addr.wrapping_sub(start) < len as u16,
+            None => false,
+        }
+    }
+}
 
 impl ByteEmuApp {
     pub fn show_memory_monitor(&mut self, ctx: &egui::Context) {
@@ -15,68 +69,347 @@ impl ByteEmuApp {
     }
 
     fn ui_memory_monitor(&mut self, ui: &mut egui::Ui) {
-        let addr_str = &mut self.state.memory_window_range_str.0;
-        let size_str = &mut self.state.memory_window_range_str.1;
-        self.state.memory_window_text_area.clear();
+        let memory = self.emu.memory();
+        self.memory_monitor.update(memory, self.emu.cycle());
 
         ui.style_mut().override_font_id = Some(egui::FontId::monospace(14.0));
+        self.ui_memory_monitor_range(ui);
+        self.ui_memory_monitor_search(ui);
+        self.ui_memory_monitor_bookmarks(ui);
+        ui.add_space(10.0);
+
+        ScrollArea::both().show(ui, |ui| {
+            ui.vertical(|ui| {
+                self.ui_memory_monitor_scroll(ui);
+            });
+        });
+    }
+
+    fn ui_memory_monitor_range(&mut self, ui: &mut egui::Ui) {
+        let (addr_str, size_str) = &mut self.state.memory_window_range_str;
+
+        // the text boxes are only parsed when they are edited
         ui.horizontal(|ui| {
             ui.label("addr:");
-            ui.text_edit_singleline(addr_str);
+            if ui.text_edit_singleline(addr_str).changed() {
+                // labels from the symbol file work as well
+                let addr = self
+                    .symbols
+                    .addr(addr_str.trim())
+                    .or_else(|| parse_addr(addr_str));
+                if let Some(addr) = addr {
+                    self.state.memory_window_range.0 = addr;
+                }
+            }
         });
         ui.horizontal(|ui| {
             ui.label("size:");
-            ui.text_edit_singleline(size_str);
+            if ui.text_edit_singleline(size_str).changed() {
+                let size = u32::from_str_radix(size_str.trim().trim_start_matches("0x"), 16);
+                if let Ok(size) = size {
+                    self.state.memory_window_range.1 = size.min(u16::MAX as u32) as u16;
+                }
+            }
         });
-        ui.add_space(10.0);
+    }
 
-        if let (Ok(start), Ok(size)) = (
-            u16::from_str_radix(addr_str.trim_start_matches("0x"), 16),
-            u32::from_str_radix(size_str.trim_start_matches("0x"), 16),
-        ) {
-            self.state.memory_window_range = (start, size.saturating_sub(1) as u16);
+    fn ui_memory_monitor_search(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("find:");
+            let response = ui.text_edit_singleline(&mut self.state.memory_search);
+            ui.checkbox(&mut self.state.memory_search_string, "string");
+
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui.button("next").clicked() || submitted {
+                self.search_memory();
+            }
+        });
+
+        if !self.memory_monitor.search_status.is_empty() {
+            ui.label(RichText::new(&self.memory_monitor.search_status).color(Color32::GRAY));
         }
+    }
 
-        ScrollArea::both().show(ui, |ui| {
-            ui.vertical(|ui| {
-                self.ui_memory_monitor_scroll(ui);
+    fn ui_memory_monitor_bookmarks(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("bookmarks").show(ui, |ui| {
+            let mut goto = None;
+
+            self.state.memory_bookmarks.retain(|(addr, name)| {
+                ui.horizontal(|ui| {
+                    let keep = !ui.small_button("x").clicked();
+                    if ui.button(format!("{addr:04x} {name}")).clicked() {
+                        goto = Some(*addr);
+                    }
+                    keep
+                })
+                .inner
             });
+
+            let addr = self
+                .memory_monitor
+                .cursor
+                .map_or(self.state.memory_window_range.0, |cursor| cursor.addr);
+            if ui.button(format!("bookmark {addr:04x}")).clicked() {
+                let name = self.symbols.label(addr).unwrap_or_default().to_owned();
+                self.state.memory_bookmarks.push((addr, name));
+            }
+
+            if let Some(addr) = goto {
+                self.goto_memory(addr);
+            }
         });
     }
 
     fn ui_memory_monitor_scroll(&mut self, ui: &mut egui::Ui) {
-        let mut count = self.state.memory_window_range.0 as usize;
-        let mem_slice = self.emu.get_memory_region(self.state.memory_window_range);
-
-        mem_slice.chunks(16).for_each(|chunk| {
-            let ascii = format!(
-                "{: <16}",
-                chunk
-                    .iter()
-                    .map(|b| match b {
-                        // printable range
-                        0x20..=0x7e => *b as char,
-                        _ => '.',
-                    })
-                    .collect::<String>()
-            );
-            let mut bytes = format!("{chunk:02x?}").replace(['[', ']', ','], "");
-            if bytes.len() > 24 {
-                bytes.insert(24, ' ');
+        let (start, size) = self.state.memory_window_range;
+        let end = (start as usize + size as usize).min(0x10000);
+        let mut cells = Vec::new();
+
+        ui.spacing_mut().item_spacing.x = 0.0;
+        let grid = ui.scope(|ui| {
+            for row in (start as usize..end).step_by(16) {
+                let row_end = (row + 15).min(end - 1);
+
+                ui.horizontal(|ui| {
+                    ui.add(Label::new(
+                        RichText::new(format!("{row:04x} ")).color(ADDR_COLOR),
+                    ));
+
+                    for addr in row..row + 16 {
+                        let text = match addr <= row_end {
+                            true => format!(" {:02x}", self.emu.peek(addr as u16)),
+                            false => "   ".to_string(),
+                        };
+                        let text = match addr == row + 8 {
+                            true => format!(" {text}"),
+                            false => text,
+                        };
+                        let response =
+                            ui.add(Label::new(self.memory_cell(addr as u16, text, false)));
+                        if addr <= row_end {
+                            cells.push((response.rect, addr as u16, false));
+                        }
+                    }
+
+                    ui.add(Label::new(RichText::new("  |").color(ADDR_COLOR)));
+                    for addr in row..=row_end {
+                        let text = match self.emu.peek(addr as u16) {
+                            // printable range
+                            b @ 0x20..=0x7e => (b as char).to_string(),
+                            _ => ".".to_string(),
+                        };
+                        let response =
+                            ui.add(Label::new(self.memory_cell(addr as u16, text, true)));
+                        cells.push((response.rect, addr as u16, true));
+                    }
+                    ui.add(Label::new(RichText::new("|").color(ADDR_COLOR)));
+                });
             }
+        });
+
+        let id = ui.id().with("memory_cursor");
+        let response = ui.interact(grid.response.rect, id, Sense::click());
+        if response.clicked() {
+            self.select_memory_cell(&cells, response.interact_pointer_pos());
+            response.request_focus();
+        }
 
-            ui.horizontal(|ui| {
-                ui.add(Label::new(
-                    RichText::new(format!("{count:04x}")).color(Color32::from_rgb(100, 149, 237)),
-                ));
-                ui.add(Label::new(
-                    RichText::new(format!("{bytes: <48}")).color(Color32::LIGHT_GRAY),
-                ));
-                ui.add(Label::new(
-                    RichText::new(format!("|{ascii}|")).color(Color32::from_rgb(100, 149, 237)),
-                ));
+        if response.has_focus() {
+            ui.memory_mut(|mem| {
+                mem.set_focus_lock_filter(
+                    id,
+                    egui::EventFilter {
+                        tab: true,
+                        horizontal_arrows: true,
+                        vertical_arrows: true,
+                        escape: false,
+                    },
+                )
             });
-            count += chunk.len();
-        });
+            self.edit_memory(ui);
+        } else {
+            self.memory_monitor.nibble = None;
+        }
+    }
+
+    fn memory_cell(&self, addr: u16, text: String, ascii: bool) -> RichText {
+        let monitor = &self.memory_monitor;
+        let mut text = RichText::new(text);
+
+        text = match monitor.changed(addr) {
+            true => text.color(CHANGED_COLOR),
+            false => text.color(Color32::LIGHT_GRAY),
+        };
+        if monitor.matched(addr) {
+            text = text.background_color(MATCH_COLOR);
+        }
+        if let Some(cursor) = monitor.cursor {
+            if cursor.addr == addr {
+                text = match cursor.ascii == ascii {
+                    true => text.background_color(CURSOR_COLOR).color(Color32::WHITE),
+                    false => text.underline(),
+                };
+            }
+        }
+
+        text
+    }
+
+    fn select_memory_cell(&mut self, cells: &[(Rect, u16, bool)], pos: Option<egui::Pos2>) {
+        let Some(pos) = pos else {
+            return;
+        };
+
+        if let Some((_, addr, ascii)) = cells.iter().find(|(rect, ..)| rect.contains(pos)) {
+            self.memory_monitor.cursor = Some(Cursor {
+                addr: *addr,
+                ascii: *ascii,
+            });
+            self.memory_monitor.nibble = None;
+        }
     }
+
+    // hex digits or characters typed while the monitor has focus are
+    // written to memory at the cursor, arrows move it, tab switches columns.
+    // the writes go through the bus like the cpu's, so rom can't be edited.
+    fn edit_memory(&mut self, ui: &mut egui::Ui) {
+        let Some(mut cursor) = self.memory_monitor.cursor else {
+            return;
+        };
+
+        for event in ui.input(|i| i.events.clone()) {
+            match event {
+                egui::Event::Key {
+                    key, pressed: true, ..
+                } => {
+                    let offset: i32 = match key {
+                        egui::Key::ArrowLeft => -1,
+                        egui::Key::ArrowRight => 1,
+                        egui::Key::ArrowUp => -16,
+                        egui::Key::ArrowDown => 16,
+                        egui::Key::Tab => {
+                            cursor.ascii = !cursor.ascii;
+                            0
+                        }
+                        egui::Key::Escape => {
+                            self.memory_monitor.cursor = None;
+                            self.memory_monitor.nibble = None;
+                            return;
+                        }
+                        _ => continue,
+                    };
+
+                    cursor.addr = cursor.addr.wrapping_add(offset as u16);
+                    self.memory_monitor.nibble = None;
+                }
+                egui::Event::Text(text) => {
+                    for c in text.chars() {
+                        if cursor.ascii {
+                            if !(' '..='~').contains(&c) {
+                                continue;
+                            }
+                            self.emu.write(cursor.addr, c as u8);
+                            cursor.addr = cursor.addr.wrapping_add(1);
+                            continue;
+                        }
+
+                        let Some(digit) = c.to_digit(16).map(|d| d as u8) else {
+                            continue;
+                        };
+                        match self.memory_monitor.nibble.take() {
+                            Some(hi) => {
+                                self.emu.write(cursor.addr, hi << 4 | digit);
+                                cursor.addr = cursor.addr.wrapping_add(1);
+                            }
+                            None => self.memory_monitor.nibble = Some(digit),
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        self.memory_monitor.cursor = Some(cursor);
+        self.scroll_memory_to(cursor.addr);
+    }
+
+    fn search_memory(&mut self) {
+        let pattern = match self.state.memory_search_string {
+            true => Some(self.state.memory_search.bytes().map(Some).collect()),
+            false => parse_pattern(&self.state.memory_search),
+        };
+        let monitor = &mut self.memory_monitor;
+
+        let Some(pattern) = pattern.filter(|p: &Vec<_>| !p.is_empty()) else {
+            monitor.search_status = "invalid pattern, expected hex bytes like `a9 ?? 8d`".into();
+            return;
+        };
+
+        // continue after the previous match
+        let from = match monitor.search_match {
+            Some((addr, _)) => addr as usize + 1,
+            None => monitor.cursor.map_or(0, |cursor| cursor.addr as usize),
+        };
+        let memory = self.emu.memory();
+
+        match find_pattern(&memory, &pattern, from) {
+            Some(addr) => {
+                monitor.search_match = Some((addr as u16, pattern.len()));
+                monitor.search_status = format!("found at {addr:04x}");
+                self.goto_memory(addr as u16);
+            }
+            None => {
+                monitor.search_match = None;
+                monitor.search_status = "not found".into();
+            }
+        }
+    }
+
+    fn goto_memory(&mut self, addr: u16) {
+        self.memory_monitor.cursor = Some(Cursor { addr, ascii: false });
+        self.memory_monitor.nibble = None;
+        self.scroll_memory_to(addr);
+    }
+
+    // moves the window so that it contains `addr`
+    fn scroll_memory_to(&mut self, addr: u16) {
+        let (start, size) = self.state.memory_window_range;
+        if addr >= start && addr - start < size {
+            return;
+        }
+
+        let start = addr & 0xfff0;
+        self.state.memory_window_range.0 = start;
+        self.state.memory_window_range_str.0 = format!("0x{start:04x}");
+    }
+}
+
+// hex bytes separated by whitespace, `??` matches any byte
+fn parse_pattern(s: &str) -> Option<Vec<Option<u8>>> {
+    let digits: String = s.split_whitespace().collect();
+    if !digits.is_ascii() || digits.len() % 2 != 0 {
+        return None;
+    }
+
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| match &digits[i..i + 2] {
+            "??" => Some(None),
+            byte => u8::from_str_radix(byte, 16).ok().map(Some),
+        })
+        .collect()
+}
+
+// searches the whole address space starting at `from`, wrapping around
+fn find_pattern(memory: &[u8], pattern: &[Option<u8>], from: usize) -> Option<usize> {
+    let matches = |addr: usize| {
+        pattern.iter().enumerate().all(|(i, byte)| match byte {
+            Some(byte) => memory[(addr + i) % memory.len()] == *byte,
+            None => true,
+        })
+    };
+
+    (from..memory.len())
+        .chain(0..from.min(memory.len()))
+        .find(|addr| matches(*addr))
 }
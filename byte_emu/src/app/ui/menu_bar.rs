@@ -1,4 +1,7 @@
-use crate::app::{ByteEmuApp, FileProcesserMessage, State};
+use crate::{
+    app::{ByteEmuApp, FileProcesserMessage, State},
+    emu::movie::MovieState,
+};
 
 impl ByteEmuApp {
     pub fn show_menu_bar(&mut self, ctx: &egui::Context) {
@@ -15,6 +18,19 @@ impl ByteEmuApp {
             if ui.button("About").clicked() {
                 self.state.is_about_open = !self.state.is_about_open;
             }
+
+            ui.with_layout(
+                egui::Layout::right_to_left(egui::Align::Center),
+                |ui| match self.emu.movie_state() {
+                    Some(MovieState::Recording(movie)) => {
+                        ui.label(format!("recording, {} frames", movie.len()));
+                    }
+                    Some(MovieState::Playing { movie, frame }) => {
+                        ui.label(format!("playing, frame {frame} / {}", movie.len()));
+                    }
+                    None => (),
+                },
+            );
         });
     }
 
@@ -32,7 +48,14 @@ impl ByteEmuApp {
                     .read(|name, data| SourceFile((name, data)));
                 ui.close_menu();
             }
+            if ui.button("Load symbol file").clicked() {
+                self.file_processer
+                    .read(|name, data| SymbolFile((name, data)));
+                ui.close_menu();
+            }
 
+            ui.separator();
+            self.ui_movie_buttons(ui);
             ui.separator();
 
             if ui.button("Reset GUI state").clicked() {
@@ -47,6 +70,53 @@ impl ByteEmuApp {
         });
     }
 
+    fn ui_movie_buttons(&mut self, ui: &mut egui::Ui) {
+        match self.emu.movie_state() {
+            Some(MovieState::Recording(_)) => {
+                if ui.button("Stop recording").clicked() {
+                    self.last_movie = self.emu.stop_movie();
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if let Some(movie) = &self.last_movie {
+                        self.file_processer
+                            .save("recording.bytm".to_string(), movie.to_bytes());
+                    }
+                    ui.close_menu();
+                }
+            }
+            Some(MovieState::Playing { .. }) => {
+                if ui.button("Stop playback").clicked() {
+                    self.emu.stop_movie();
+                    ui.close_menu();
+                }
+            }
+            None => {
+                // recording starts the program over
+                if ui.button("Record movie").clicked() {
+                    self.emu.record_movie();
+                    self.terminal.clear();
+                    ui.close_menu();
+                }
+                if ui.button("Play movie").clicked() {
+                    self.file_processer
+                        .read(|name, data| FileProcesserMessage::MovieFile((name, data)));
+                    ui.close_menu();
+                }
+                if ui
+                    .add_enabled(
+                        self.last_movie.is_some(),
+                        egui::Button::new("Replay last recording"),
+                    )
+                    .clicked()
+                {
+                    if let Some(movie) = self.last_movie.clone() {
+                        self.play_movie(movie);
+                    }
+                    ui.close_menu();
+                }
+            }
+        }
+    }
+
     fn ui_tools_button(&mut self, ui: &mut egui::Ui) {
         ui.menu_button("Tools", |ui| {
             if ui.button("Code Editor").clicked() {
@@ -54,15 +124,30 @@ impl ByteEmuApp {
                 ui.close_menu();
             }
 
+            if ui.button("Disassembly").clicked() {
+                self.state.is_disassembly_open = !self.state.is_disassembly_open;
+                ui.close_menu();
+            }
+
             if ui.button("Emulator Controls").clicked() {
                 self.state.is_emu_controls_open = !self.state.is_emu_controls_open;
                 ui.close_menu();
             }
 
+            if ui.button("Key Bindings").clicked() {
+                self.state.is_key_bindings_open = !self.state.is_key_bindings_open;
+                ui.close_menu();
+            }
+
             if ui.button("Memory Monitor").clicked() {
                 self.state.is_memory_monitor_open = !self.state.is_memory_monitor_open;
                 ui.close_menu();
             }
+
+            if ui.button("Terminal").clicked() {
+                self.state.is_terminal_open = !self.state.is_terminal_open;
+                ui.close_menu();
+            }
         });
     }
 }
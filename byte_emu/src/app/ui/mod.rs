@@ -1,6 +1,9 @@
 pub mod about;
 pub mod byte_console;
 pub mod code_editor;
+pub mod disassembly;
 pub mod emu_controls;
+pub mod key_bindings;
 pub mod memory_monitor;
 pub mod menu_bar;
+pub mod terminal;
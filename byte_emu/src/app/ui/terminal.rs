@@ -0,0 +1,46 @@
+use crate::app::ByteEmuApp;
+
+// older output is dropped once the terminal holds more than this
+const MAX_LEN: usize = 64 * 1024;
+
+impl ByteEmuApp {
+    pub fn show_terminal(&mut self, ctx: &egui::Context) {
+        let mut open = self.state.is_terminal_open;
+        egui::Window::new("Terminal")
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                self.ui_terminal(ui);
+            });
+        self.state.is_terminal_open = open;
+    }
+
+    fn ui_terminal(&mut self, ui: &mut egui::Ui) {
+        if ui.button("clear").clicked() {
+            self.terminal.clear();
+        }
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .max_height(240.0)
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                ui.add(egui::Label::new(egui::RichText::new(&self.terminal).monospace()).wrap());
+            });
+    }
+
+    // bytes are taken as latin-1 so that nothing the program writes gets lost
+    pub fn update_terminal(&mut self) {
+        let output = self.emu.take_output();
+        self.terminal
+            .extend(output.iter().filter(|c| **c != b'\r').map(|c| *c as char));
+
+        if self.terminal.len() > MAX_LEN {
+            let mut start = self.terminal.len() - MAX_LEN;
+            while !self.terminal.is_char_boundary(start) {
+                start += 1;
+            }
+            self.terminal.drain(..start);
+        }
+    }
+}
@@ -1,23 +1,99 @@
-use super::rand;
-use std::collections::HashSet;
+use super::{
+    input::PLAYERS,
+    movie::{Movie, MovieState},
+    rand::{self, Random},
+    rewind::RewindBuffer,
+    snapshot::{Snapshot, MEMORY_SIZE},
+    sound::{Psg, REGISTER_COUNT as SOUND_REGISTER_COUNT},
+    terminal::Terminal,
+    video::{self, Video},
+};
+use std::{cell::RefCell, rc::Rc};
 
 use bitflags::bitflags;
-use byte_core::*;
+use byte_core::{
+    debugger::{Debugger, Frame, Goal, StopReason},
+    *,
+};
 
-const COLOR_PALETTE: [u32; 16] = [
-    0x000000FF, 0xFFFFFFFF, 0x880000FF, 0xAAFFEEFF, 0xCC44CCFF, 0x00CC55FF, 0x0000AAFF, 0xEEEE77FF,
-    0x664400FF, 0xFF7777FF, 0x333333FF, 0x777777FF, 0xAAFF66FF, 0x0088FFFF, 0x0088FFFF, 0xBBBBBBFF,
-];
-const INSTRUCTIONS_PER_FRAME: usize = 6400000 / 60;
+// the number of cpu cycles in a second, which the devices are clocked by
+pub const CLOCK_RATE: u64 = 6_400_000;
+// the console runs at 60 frames per second. that makes a line 666 2/3
+// cycles long, so the fraction is carried over and lines take 666 or 667
+const LINES_PER_SECOND: u64 = 60 * video::LINES_PER_FRAME as u64;
 
+const REG_BANK: u16 = 0xfb;
 const REG_VIDEO: u16 = 0xfd;
 const REG_RANDOM: u16 = 0xfe;
 const REG_INPUT: u16 = 0xff;
-const FRAMEBUFFER_SIZE: usize = 64 * 64;
+// offsets of the devices into the i/o page
+const IO_SOUND: u16 = 0x00;
+const IO_VIDEO: u16 = 0x10;
+const IO_TIMER: u16 = 0x50;
+const IO_TERMINAL: u16 = 0x60;
+const IO_RANDOM: u16 = 0x64;
+const IO_INPUT: u16 = 0x68;
+
+// cartridges bigger than the address space are split into banks, the
+// one selected through `REG_BANK` shows up in the window at $8000
+const BANK_SIZE: u16 = 0x4000;
+const BANK_WINDOW: u16 = 0x8000;
+
+// where the console puts its ram and rom. the i/o page is reserved for
+// memory mapped devices, which overlay the ram at a higher priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryMap {
+    pub ram: (u16, u16),
+    pub rom: Option<(u16, u16)>,
+    pub io: (u16, u16),
+}
+
+impl Default for MemoryMap {
+    fn default() -> Self {
+        Self {
+            ram: (0x0000, 0x7fff),
+            rom: Some((0x8000, 0xffff)),
+            io: (0x7f00, 0x7fff),
+        }
+    }
+}
+
+// ten seconds worth of frames, capped at 16 MiB of deltas
+const REWIND_FRAMES: usize = 60 * 10;
+const REWIND_MAX_BYTES: usize = 16 << 20;
 
 pub struct ByteEmu {
     cpu: cpu::CPU,
-    rand: Box<dyn Iterator<Item = u32>>,
+    map: MemoryMap,
+    mapper: Option<bus::Handle>,
+    sound: Rc<RefCell<Psg>>,
+    timer: Rc<RefCell<bus::Timer>>,
+    terminal: Rc<RefCell<Terminal>>,
+    rand: Rc<RefCell<Random>>,
+    // the program that was loaded last, and where, for recording movies
+    program: Vec<u8>,
+    start: u16,
+    movie: Option<MovieState>,
+    // the seed programs start out with, they can reseed through the
+    // generator's registers
+    seed: u32,
+    rewind: RewindBuffer,
+    rewind_cursor: Option<usize>,
+    paused: bool,
+    pause_after_frame: bool,
+    stop_reason: Option<StopReason>,
+    // the scanline being drawn and the cycles spent on it so far, in
+    // `LINES_PER_SECOND`ths of a cycle. a frame that got interrupted by the
+    // debugger continues from here.
+    line: usize,
+    line_cycles: u64,
+    // an interrupt request from the video waiting for the cpu to clear its interrupt flag
+    irq: bool,
+    // the buttons held down in the current frame, taken when it starts
+    frame_input: Option<[ByteInputState; PLAYERS]>,
+    // the frame being drawn and the last finished one
+    frame: video::Frame,
+    display: video::Frame,
 }
 
 bitflags! {
@@ -33,75 +109,544 @@ bitflags! {
     }
 }
 
-impl From<HashSet<egui::Key>> for ByteInputState {
-    fn from(val: HashSet<egui::Key>) -> ByteInputState {
-        use egui::Key::*;
-        let mut state = ByteInputState::empty();
-
-        #[rustfmt::skip]
-        val.iter().for_each(|key| match key {
-            A          => state.insert(ByteInputState::SELECT),
-            S          => state.insert(ByteInputState::START),
-            D          => state.insert(ByteInputState::A),
-            F          => state.insert(ByteInputState::B),
-            ArrowUp    => state.insert(ByteInputState::UP),
-            ArrowDown  => state.insert(ByteInputState::DOWN),
-            ArrowLeft  => state.insert(ByteInputState::LEFT),
-            ArrowRight => state.insert(ByteInputState::RIGHT),
-            _          => ()
-        });
-
-        state
+impl Default for ByteEmu {
+    fn default() -> Self {
+        Self::new(MemoryMap::default())
     }
 }
 
-impl Default for ByteEmu {
-    fn default() -> Self {
+impl ByteEmu {
+    pub fn new(map: MemoryMap) -> Self {
         let mut cpu = cpu::CPU::default();
+        cpu.bus.attach_ram(map.ram.0, map.ram.1).unwrap();
+        if let Some((lo, hi)) = map.rom {
+            let size = hi as usize - lo as usize + 1;
+            cpu.bus.attach_rom(lo, &vec![0; size]).unwrap();
+        }
+
+        let sound = Rc::new(RefCell::new(Psg::default()));
+        let lo = map.io.0 + IO_SOUND;
+        let region = bus::Region::new(lo, lo + SOUND_REGISTER_COUNT - 1);
         cpu.bus
-            .attach(0x0000, 0xffff, super::ram::Ram::default())
+            .attach_region(region.with_priority(1), sound.clone())
             .unwrap();
 
-        Self {
+        let timer = Rc::new(RefCell::new(bus::Timer::default()));
+        let lo = map.io.0 + IO_TIMER;
+        let region = bus::Region::new(lo, lo + bus::timer::REGISTER_COUNT - 1);
+        cpu.bus
+            .attach_region(region.with_priority(1), timer.clone())
+            .unwrap();
+
+        let terminal = Rc::new(RefCell::new(Terminal::default()));
+        let addr = map.io.0 + IO_TERMINAL;
+        cpu.bus
+            .attach_region(
+                bus::Region::new(addr, addr).with_priority(1),
+                terminal.clone(),
+            )
+            .unwrap();
+
+        let seed = rand::random_seed() as u32;
+        let random = Rc::new(RefCell::new(Random::new(seed)));
+        let lo = map.io.0 + IO_RANDOM;
+        let region = bus::Region::new(lo, lo + rand::REGISTER_COUNT - 1);
+        cpu.bus
+            .attach_region(region.with_priority(1), random.clone())
+            .unwrap();
+
+        let mut emu = Self {
             cpu,
-            rand: Box::new(rand::random_numbers(rand::random_seed() as u32)),
-        }
+            map,
+            mapper: None,
+            sound,
+            timer,
+            terminal,
+            rand: random,
+            seed,
+            program: Vec::new(),
+            start: 0,
+            movie: None,
+            rewind: RewindBuffer::new(REWIND_FRAMES, REWIND_MAX_BYTES),
+            rewind_cursor: None,
+            paused: false,
+            pause_after_frame: false,
+            stop_reason: None,
+            line: 0,
+            line_cycles: 0,
+            irq: false,
+            frame_input: None,
+            frame: video::Frame::new(0, 0),
+            display: video::Frame::new(0, 0),
+        };
+        emu.reset_palette();
+        emu.reset_frame();
+        emu
     }
-}
 
-impl ByteEmu {
+    pub fn memory_map(&self) -> MemoryMap {
+        self.map
+    }
+
+    // the generator is reseeded right away and whenever a program is loaded
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed;
+        self.rand.borrow_mut().reseed(seed);
+    }
+
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    // starts the program on a clean machine, so that what it does only
+    // depends on the seed and the input. this ends any movie.
     pub fn load_program(&mut self, program: &[u8], start: u16) {
-        self.cpu.load(program, start);
+        self.movie = None;
+        self.program = program.to_vec();
+        self.start = start;
+
+        if let Some(handle) = self.mapper.take() {
+            self.cpu.bus.detach(handle).ok();
+        }
+
+        self.cpu.reg = cpu::Registers::default();
+        self.cpu.cycle = 0;
+        self.fill_memory(&vec![0; MEMORY_SIZE]);
+
+        let len = program.len().min(MEMORY_SIZE - start as usize);
+        self.cpu.load(&program[..len], start);
+        // attached after loading, or the byte at `REG_BANK` would select a bank
+        if program.len() > MEMORY_SIZE {
+            self.attach_mapper(program);
+        }
+        // loading writes the image to the devices as well
+        *self.timer.borrow_mut() = bus::Timer::default();
+        *self.sound.borrow_mut() = Psg::default();
+        self.terminal.borrow_mut().take_output();
+        self.rand.borrow_mut().reseed(self.seed);
+        self.next_random();
+        self.reset_palette();
         self.cpu.interrupt(cpu::Interrupt::RST);
+
+        self.rewind.clear();
+        self.rewind_cursor = None;
+        self.reset_frame();
+    }
+
+    // restarts the current program and records the input from then on
+    pub fn record_movie(&mut self) {
+        let program = std::mem::take(&mut self.program);
+        self.load_program(&program, self.start);
+
+        self.movie = Some(MovieState::Recording(Movie {
+            seed: self.seed,
+            start: self.start,
+            program,
+            inputs: Vec::new(),
+        }));
     }
 
-    pub fn framebuffer(&self) -> [u32; FRAMEBUFFER_SIZE] {
-        let mut frame = [0u32; FRAMEBUFFER_SIZE];
-        let video_ptr = (self.cpu.bus.read(REG_VIDEO) as u16 & 0xf) << 0xc;
+    // the input of the movie takes the place of the one passed to `step`
+    // until it runs out
+    pub fn play_movie(&mut self, movie: Movie) {
+        self.set_seed(movie.seed);
+        self.load_program(&movie.program, movie.start);
+        self.movie = Some(MovieState::Playing { movie, frame: 0 });
+    }
+
+    // ends the recording or playback, returns the movie if one was recorded
+    pub fn stop_movie(&mut self) -> Option<Movie> {
+        match self.movie.take() {
+            Some(MovieState::Recording(movie)) => Some(movie),
+            _ => None,
+        }
+    }
+
+    pub fn movie_state(&self) -> Option<&MovieState> {
+        self.movie.as_ref()
+    }
 
-        frame.iter_mut().enumerate().for_each(|(i, p)| {
-            let color = self.cpu.bus.read(video_ptr + i as u16) & 0xf;
-            *p = COLOR_PALETTE[color as usize];
+    // the input for the frame that is about to start
+    fn next_input(&mut self, input_state: [ByteInputState; PLAYERS]) -> [ByteInputState; PLAYERS] {
+        match &mut self.movie {
+            Some(MovieState::Recording(movie)) => {
+                movie.inputs.push(input_state.map(|state| state.bits()));
+                input_state
+            }
+            Some(MovieState::Playing { movie, frame }) => match movie.inputs.get(*frame) {
+                Some(bits) => {
+                    *frame += 1;
+                    bits.map(ByteInputState::from_bits_truncate)
+                }
+                None => {
+                    self.movie = None;
+                    input_state
+                }
+            },
+            None => input_state,
+        }
+    }
+
+    fn attach_mapper(&mut self, program: &[u8]) {
+        let mut rom = program.to_vec();
+        rom.resize(rom.len().next_multiple_of(BANK_SIZE as usize), 0);
+
+        // the window initially shows the same bytes the ram would
+        let mapper = bus::Mapper::new(&rom, BANK_SIZE).map(|mapper| {
+            mapper
+                .with_window(BANK_WINDOW, (BANK_WINDOW / BANK_SIZE) as usize)
+                .with_select(REG_BANK)
+                .with_priority(1)
         });
-        frame
+
+        match mapper.and_then(|mapper| self.cpu.bus.attach_mapper(mapper)) {
+            Ok(handle) => self.mapper = Some(handle),
+            Err(err) => log::error!("{err}"),
+        }
     }
 
-    pub fn step(&mut self, input_state: ByteInputState) {
-        self.cpu.bus.write(REG_INPUT, input_state.bits());
+    // the last frame that was drawn in full
+    pub fn framebuffer(&self) -> &video::Frame {
+        &self.display
+    }
+
+    fn video(&self) -> Video {
+        let peek = |addr| self.cpu.bus.peek(addr);
+        Video::read(peek, peek(REG_VIDEO), self.map.io.0 + IO_VIDEO)
+    }
+
+    // starts over at the top of a frame and draws the screen as the
+    // memory currently describes it, for when the memory changed behind
+    // the frame loop's back
+    fn reset_frame(&mut self) {
+        let video = self.video();
+        let peek = |addr| self.cpu.bus.peek(addr);
+
+        let mut frame = video::Frame::new(video.width(), video.height());
+        for (y, line) in frame.pixels.chunks_exact_mut(frame.width).enumerate() {
+            video.render_colors(peek, y, line);
+        }
+        self.display = frame;
+
+        self.line = 0;
+        self.line_cycles = 0;
+        self.irq = false;
+        self.frame_input = None;
+        self.begin_line();
+    }
+
+    fn begin_line(&mut self) {
+        let io = self.map.io.0 + IO_VIDEO;
+        let mut status = self.cpu.bus.peek(io + video::STATUS) & !video::STATUS_VBLANK;
+
+        if self.line >= video::VISIBLE_LINES {
+            status |= video::STATUS_VBLANK;
+        }
+        if self.line == video::VISIBLE_LINES {
+            std::mem::swap(&mut self.frame, &mut self.display);
+            self.irq = true;
+        }
+
+        let compare = self.cpu.bus.peek(io + video::LINE_COMPARE) as usize;
+        let control = self.cpu.bus.peek(io + video::CONTROL);
+        if control & video::CONTROL_LINE_IRQ != 0 && compare == self.line {
+            status |= video::STATUS_LINE;
+            self.irq = true;
+        }
+
+        self.cpu.bus.write(io + video::LINE, self.line as u8);
+        self.cpu.bus.write(io + video::STATUS, status);
+    }
+
+    // lines are drawn once they're over, so that whatever the program
+    // changed in the meantime shows up on them
+    fn end_line(&mut self) {
+        if self.line >= video::VISIBLE_LINES {
+            return;
+        }
+
+        let video = self.video();
+        if self.line == 0 {
+            self.frame = video::Frame::new(video.width(), video.height());
+        }
+        // the resolution only changes at the start of a frame
+        if video.width() != self.frame.width {
+            return;
+        }
+
+        let (width, height) = (self.frame.width, self.frame.height);
+        let y = self.line * height / video::VISIBLE_LINES;
+        let peek = |addr| self.cpu.bus.peek(addr);
+        video.render_colors(peek, y, &mut self.frame.pixels[y * width..][..width]);
+    }
 
-        for _ in 0..INSTRUCTIONS_PER_FRAME {
-            if let Some(n) = self.rand.next() {
-                self.cpu.bus.write(REG_RANDOM, n as u8);
+    // lets `cycles` cpu cycles worth of time pass for the devices
+    fn clock(&mut self, cycles: u64) {
+        self.sound.borrow_mut().clock(cycles);
+        self.timer.borrow_mut().clock(cycles);
+        self.line_cycles += cycles * LINES_PER_SECOND;
+
+        while self.line_cycles >= CLOCK_RATE && self.line < video::LINES_PER_FRAME {
+            self.line_cycles -= CLOCK_RATE;
+            self.end_line();
+            self.line += 1;
+
+            if self.line < video::LINES_PER_FRAME {
+                self.begin_line();
+            }
+        }
+    }
+
+    // every instruction sees a new number in `REG_RANDOM`
+    fn next_random(&mut self) {
+        if let Some(n) = self.rand.borrow_mut().next() {
+            self.cpu.bus.write(REG_RANDOM, n as u8);
+        }
+    }
+
+    // programs start out with the default palette, whatever their image
+    // holds at its address
+    fn reset_palette(&mut self) {
+        let palette = self.map.io.0 + IO_VIDEO + video::PALETTE;
+
+        for (i, color) in video::DEFAULT_PALETTE.iter().enumerate() {
+            let [r, g, b, _] = color.to_be_bytes();
+            self.cpu.load(&[r, g, b], palette + i as u16 * 3);
+        }
+    }
+
+    // `input_state` holds the buttons of each player. player 1 shows up in
+    // `REG_INPUT` as well as the input port.
+    pub fn step(&mut self, input_state: [ByteInputState; PLAYERS]) -> Option<StopReason> {
+        if self.paused {
+            return None;
+        }
+
+        // continuing after seeking discards the frames that came after it
+        if let Some(index) = self.rewind_cursor.take() {
+            self.rewind.truncate(index);
+        }
+
+        // a frame interrupted by the debugger keeps its input
+        let input = match self.frame_input {
+            Some(input) => input,
+            None => self.next_input(input_state),
+        };
+        self.frame_input = Some(input);
+        self.cpu.bus.write(REG_INPUT, input[0].bits());
+        let port = self.map.io.0 + IO_INPUT;
+        for (player, state) in input.iter().enumerate() {
+            self.cpu.bus.write(port + player as u16, state.bits());
+        }
+
+        while self.line < video::LINES_PER_FRAME {
+            let cycle = self.cpu.cycle;
+            let irq = self.irq || self.timer.borrow().irq();
+            if irq && !self.cpu.reg.p.contains(cpu::Flags::INTERRUPT) {
+                self.irq = false;
+                self.cpu.interrupt(cpu::Interrupt::IRQ);
+            }
+
+            // breakpoints stop before the instruction is executed, which
+            // mustn't cost a number or debugging would change the sequence
+            let before = self.cpu.cycle;
+            let result = self.cpu.debug_step();
+            if result.is_err() || self.cpu.cycle != before {
+                self.next_random();
             }
-            if let Err(err) = self.cpu.step() {
-                log::error!("{err}");
-            };
+            self.clock(self.cpu.cycle - cycle);
+
+            match result {
+                Ok(Some(reason)) => {
+                    log::info!("stopped: {reason:x?}");
+                    self.paused = true;
+                    self.stop_reason = Some(reason);
+                    return Some(reason);
+                }
+                Ok(None) => (),
+                Err(err) => {
+                    log::error!("{err}");
+                    // unrecognized opcodes are skipped like a nop would be
+                    self.clock(2);
+                }
+            }
+        }
+
+        self.line = 0;
+        self.frame_input = None;
+        self.begin_line();
+        self.rewind.push(&self.snapshot());
+
+        if self.pause_after_frame {
+            self.pause_after_frame = false;
+            self.paused = true;
+        }
+
+        None
+    }
+
+    // executes a single instruction outside of the frame loop, so neither
+    // the input and random registers nor the scanlines are touched
+    pub fn step_instruction(&mut self) -> Result<Option<StopReason>, Error> {
+        self.cpu.debug_step()
+    }
+
+    // the audio produced since the last call, see `sound::SAMPLE_RATE`
+    pub fn take_samples(&mut self) -> Vec<f32> {
+        self.sound.borrow_mut().take_samples()
+    }
+
+    // the bytes written to the terminal port since the last call
+    pub fn take_output(&mut self) -> Vec<u8> {
+        self.terminal.borrow_mut().take_output()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+        self.cpu.debugger.cancel_goal();
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.stop_reason = None;
+    }
+
+    // resumes the emulation until `goal` is reached or something else stops it
+    pub fn run_until(&mut self, goal: Goal) {
+        self.cpu.set_goal(goal);
+        self.resume();
+    }
+
+    // finishes the current frame and pauses again
+    pub fn frame_advance(&mut self) {
+        self.pause_after_frame = true;
+        self.resume();
+    }
+
+    pub fn pc(&self) -> u16 {
+        self.cpu.reg.pc
+    }
+
+    pub fn peek(&self, addr: u16) -> u8 {
+        self.cpu.bus.peek(addr)
+    }
+
+    // unlike the cpu, this can write to rom
+    pub fn poke(&mut self, addr: u16, byte: u8) {
+        self.cpu.bus.poke(addr, byte);
+    }
+
+    // writes a byte the way the cpu would, devices see it and rom is left alone
+    pub fn write(&mut self, addr: u16, byte: u8) {
+        self.cpu.bus.write(addr, byte);
+        self.cpu.bus.take_fault();
+    }
+
+    // the whole address space as the cpu currently sees it
+    pub fn memory(&self) -> Vec<u8> {
+        self.cpu.bus.get_memory_region((0x0000, 0xffff))
+    }
+
+    pub fn debugger(&mut self) -> &mut Debugger {
+        &mut self.cpu.debugger
+    }
+
+    pub fn registers(&mut self) -> &mut cpu::Registers {
+        &mut self.cpu.reg
+    }
+
+    pub fn cycle(&self) -> u64 {
+        self.cpu.cycle
+    }
+
+    pub fn call_stack(&self) -> &[Frame] {
+        self.cpu.debugger.call_stack()
+    }
+
+    pub fn stop_reason(&self) -> Option<StopReason> {
+        self.stop_reason
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        let rand = self.rand.borrow().state();
+        let timer = self.timer.borrow().clone();
+        let sound = self.sound.borrow().snapshot();
+        Snapshot::new(
+            self.cpu.reg,
+            self.cpu.cycle,
+            rand,
+            timer,
+            sound,
+            self.memory().into(),
+        )
+    }
+
+    // writes a whole address space worth of memory, except for the devices
+    // whose registers have side effects. the terminal would print all of
+    // it, the timer would restart its counters and lose its flags and the
+    // sound channels would restart their envelopes. their state is
+    // restored separately.
+    fn fill_memory(&mut self, memory: &[u8]) {
+        let io = self.map.io.0 as usize;
+        let devices = [
+            (IO_SOUND, SOUND_REGISTER_COUNT),
+            (IO_TIMER, bus::timer::REGISTER_COUNT),
+            (IO_TERMINAL, 1),
+        ]
+        .map(|(lo, count)| io + lo as usize..io + (lo + count) as usize);
+
+        memory
+            .iter()
+            .enumerate()
+            .filter(|(addr, _)| !devices.iter().any(|device| device.contains(addr)))
+            .for_each(|(addr, byte)| self.cpu.bus.poke(addr as u16, *byte));
+    }
+
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.fill_memory(&snapshot.memory);
+        *self.timer.borrow_mut() = snapshot.timer.clone();
+        self.sound.borrow_mut().restore(&snapshot.sound);
+
+        self.cpu.reg = snapshot.reg;
+        self.cpu.cycle = snapshot.cycle;
+        self.rand.borrow_mut().set_state(snapshot.rand);
+        self.reset_frame();
+    }
+
+    // movies can't go back in time, these do nothing while one is active
+    pub fn rewind(&mut self) {
+        if self.movie.is_some() {
+            return;
+        }
+        if let Some(index) = self.rewind_cursor.take() {
+            self.rewind.truncate(index);
+        }
+
+        if let Some(snapshot) = self.rewind.rewind() {
+            self.restore(&snapshot);
         }
+    }
+
+    pub fn seek(&mut self, index: usize) {
+        if self.movie.is_some() {
+            return;
+        }
+        if let Some(snapshot) = self.rewind.get(index) {
+            self.restore(&snapshot);
+            self.rewind_cursor = Some(index);
+        }
+    }
 
-        self.cpu.interrupt(cpu::Interrupt::IRQ);
+    pub fn rewind_position(&self) -> usize {
+        self.rewind_cursor
+            .unwrap_or(self.rewind.len().saturating_sub(1))
     }
 
-    pub fn get_memory_region(&self, range: (u16, u16)) -> &[u8] {
-        self.cpu.bus.get_memory_region(range)
+    pub fn rewind_len(&self) -> usize {
+        self.rewind.len()
     }
 }
@@ -0,0 +1,30 @@
+// a 4x4 font for the text mode, covering ascii from the space up to the
+// underscore. every glyph is four rows of a nibble each, top row first,
+// with the left pixel in the high bit. the rightmost column is left
+// empty to keep the characters apart.
+const FONT: [u16; 64] = [
+    0x0000, 0x4404, 0xaa00, 0xaeae, 0xec6e, 0xa28a, 0x44ae, 0x4400, 0x2442, 0x8448, 0xa4a0, 0x04e4,
+    0x0048, 0x00e0, 0x0004, 0x2248, 0xeaae, 0x4c4e, 0xc24e, 0xe62e, 0xaae2, 0xec2c, 0x8eae, 0xe244,
+    0xeeae, 0xeae2, 0x0404, 0x040c, 0x0686, 0x0e0e, 0x0c2c, 0xc204, 0xea86, 0x4aea, 0xceac, 0x6886,
+    0xcaac, 0xec8e, 0xe8c8, 0x68a6, 0xaeaa, 0xe44e, 0x22a4, 0xacaa, 0x888e, 0xaeea, 0xcaaa, 0x4aa4,
+    0xcac8, 0x4aa6, 0xcaca, 0x682c, 0xe444, 0xaaae, 0xaaa4, 0xaaee, 0xa44a, 0xaa44, 0xe48e, 0xc88c,
+    0x8842, 0x6226, 0x4a00, 0x000e,
+];
+
+pub const GLYPH_SIZE: usize = 4;
+
+// lowercase letters show up as capitals, the rest of the upper half of
+// ascii folds onto the symbols below it and control characters are blank
+pub fn glyph(c: u8) -> u16 {
+    match c & 0x7f {
+        c @ 0x20..=0x5f => FONT[c as usize - 0x20],
+        c @ 0x60..=0x7f => FONT[c as usize - 0x40],
+        _ => 0,
+    }
+}
+
+// whether the pixel at (`x`, `y`) of a glyph is set
+pub fn pixel(glyph: u16, x: usize, y: usize) -> bool {
+    let row = glyph >> ((GLYPH_SIZE - 1 - y) * GLYPH_SIZE);
+    row & (0b1000 >> x) != 0
+}
@@ -0,0 +1,128 @@
+use super::core::ByteInputState;
+#[cfg(feature = "gui")]
+use std::collections::HashSet;
+
+// the console has a port for each player, see `ByteEmu::step`
+pub const PLAYERS: usize = 2;
+
+// the buttons in the order of their bits
+pub const BUTTONS: [(&str, ByteInputState); 8] = [
+    ("right", ByteInputState::RIGHT),
+    ("left", ByteInputState::LEFT),
+    ("down", ByteInputState::DOWN),
+    ("up", ByteInputState::UP),
+    ("start", ByteInputState::START),
+    ("select", ByteInputState::SELECT),
+    ("b", ByteInputState::B),
+    ("a", ByteInputState::A),
+];
+
+// the keys bound to every button of every player. a button can have any
+// number of keys, but a key only ever presses one button.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct KeyBindings {
+    keys: [[Vec<egui::Key>; BUTTONS.len()]; PLAYERS],
+}
+
+#[cfg(feature = "gui")]
+impl Default for KeyBindings {
+    #[rustfmt::skip]
+    fn default() -> Self {
+        use egui::Key::*;
+
+        // in the order of `BUTTONS`
+        Self {
+            keys: [
+                [vec![ArrowRight], vec![ArrowLeft], vec![ArrowDown], vec![ArrowUp], vec![S],    vec![A],    vec![F], vec![D]],
+                [vec![L],          vec![J],         vec![K],         vec![I],       vec![Num8], vec![Num7], vec![U], vec![O]],
+            ],
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+impl KeyBindings {
+    pub fn keys(&self, player: usize, button: ByteInputState) -> &[egui::Key] {
+        &self.keys[player][index(button)]
+    }
+
+    pub fn bind(&mut self, player: usize, button: ByteInputState, key: egui::Key) {
+        self.keys
+            .iter_mut()
+            .flatten()
+            .for_each(|keys| keys.retain(|k| *k != key));
+        self.keys[player][index(button)].push(key);
+    }
+
+    pub fn unbind(&mut self, player: usize, button: ByteInputState, key: egui::Key) {
+        self.keys[player][index(button)].retain(|k| *k != key);
+    }
+
+    // the buttons every player is holding down
+    pub fn state(&self, keys_down: &HashSet<egui::Key>) -> [ByteInputState; PLAYERS] {
+        let mut state = [ByteInputState::empty(); PLAYERS];
+
+        for (player, buttons) in self.keys.iter().enumerate() {
+            for ((_, button), keys) in BUTTONS.iter().zip(buttons) {
+                if keys.iter().any(|key| keys_down.contains(key)) {
+                    state[player].insert(*button);
+                }
+            }
+        }
+
+        state
+    }
+}
+
+#[cfg(feature = "gui")]
+fn index(button: ByteInputState) -> usize {
+    button.bits().trailing_zeros() as usize
+}
+
+// the buttons of a gamepad that the console cares about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PadButton {
+    Up,
+    Down,
+    Left,
+    Right,
+    // the face buttons, by where they are on the pad
+    South,
+    East,
+    North,
+    West,
+    Start,
+    Select,
+}
+
+// a source of gamepad input, gilrs on native builds
+pub trait Gamepads {
+    // the buttons held down on every connected gamepad, in the order the
+    // gamepads were connected
+    fn poll(&mut self) -> Vec<Vec<PadButton>>;
+}
+
+// the first gamepad is player 1, the second one player 2. the buttons are
+// laid out like on a snes pad, the bottom face button is b and the right
+// one is a. the top and left face buttons double as these.
+pub fn gamepad_state(gamepads: &mut dyn Gamepads) -> [ByteInputState; PLAYERS] {
+    let mut state = [ByteInputState::empty(); PLAYERS];
+
+    for (player, pressed) in state.iter_mut().zip(gamepads.poll()) {
+        for button in pressed {
+            player.insert(match button {
+                PadButton::Up => ByteInputState::UP,
+                PadButton::Down => ByteInputState::DOWN,
+                PadButton::Left => ByteInputState::LEFT,
+                PadButton::Right => ByteInputState::RIGHT,
+                PadButton::South | PadButton::West => ByteInputState::B,
+                PadButton::East | PadButton::North => ByteInputState::A,
+                PadButton::Start => ByteInputState::START,
+                PadButton::Select => ByteInputState::SELECT,
+            });
+        }
+    }
+
+    state
+}
@@ -1,3 +1,22 @@
 pub mod core;
-pub mod ram;
+pub mod font;
+pub mod input;
+pub mod movie;
 pub mod rand;
+pub mod rewind;
+pub mod snapshot;
+pub mod sound;
+pub mod symbols;
+pub mod terminal;
+pub mod video;
+
+// accepts `$1234`, `0x1234` and `1234`, always as hex
+pub fn parse_addr(s: &str) -> Option<u16> {
+    let s = s.trim();
+    let s = s
+        .strip_prefix('$')
+        .or_else(|| s.strip_prefix("0x"))
+        .unwrap_or(s);
+
+    u16::from_str_radix(s, 16).ok()
+}
@@ -0,0 +1,134 @@
+use super::input::PLAYERS;
+use std::fmt;
+
+const MAGIC: &[u8; 4] = b"BYTM";
+const VERSION: u8 = 1;
+
+// everything needed to replay a run: the program as it was loaded, the
+// seed of the random number generator and the buttons held down in
+// every frame. the emulator is deterministic otherwise.
+//
+// movies are stored as the magic, a version byte, the seed, the start
+// address and then the program and the inputs, each prefixed with their
+// length. all numbers are little endian, lengths are 32 bits wide and
+// count bytes. every frame takes up a byte per player.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Movie {
+    pub seed: u32,
+    pub start: u16,
+    pub program: Vec<u8>,
+    // the bits of `ByteInputState` of every player for every frame
+    pub inputs: Vec<[u8; PLAYERS]>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MovieError {
+    NotAMovie,
+    UnsupportedVersion(u8),
+    Truncated,
+}
+
+impl fmt::Display for MovieError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MovieError::NotAMovie => write!(f, "not a movie file"),
+            MovieError::UnsupportedVersion(version) => {
+                write!(f, "unsupported movie version: {version}")
+            }
+            MovieError::Truncated => write!(f, "the movie file is truncated"),
+        }
+    }
+}
+
+impl std::error::Error for MovieError {}
+
+// what the emulator is doing with a movie
+#[derive(Debug, Clone)]
+pub enum MovieState {
+    Recording(Movie),
+    // `frame` is the next frame to be played
+    Playing { movie: Movie, frame: usize },
+}
+
+impl Movie {
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let inputs = self.inputs.concat();
+        let mut out = Vec::with_capacity(self.program.len() + inputs.len() + 19);
+
+        out.extend(MAGIC);
+        out.push(VERSION);
+        out.extend(self.seed.to_le_bytes());
+        out.extend(self.start.to_le_bytes());
+        for data in [&self.program, &inputs] {
+            out.extend((data.len() as u32).to_le_bytes());
+            out.extend(data);
+        }
+
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, MovieError> {
+        let mut reader = Reader(data);
+
+        if reader.take(MAGIC.len()).ok() != Some(MAGIC.as_slice()) {
+            return Err(MovieError::NotAMovie);
+        }
+        match reader.take(1)?[0] {
+            VERSION => (),
+            version => return Err(MovieError::UnsupportedVersion(version)),
+        }
+
+        let seed = u32::from_le_bytes(reader.array()?);
+        let start = u16::from_le_bytes(reader.array()?);
+        let program = reader.block()?.to_vec();
+        let inputs = reader.block()?;
+        if inputs.len() % PLAYERS != 0 {
+            return Err(MovieError::Truncated);
+        }
+        let inputs = inputs
+            .chunks_exact(PLAYERS)
+            .map(|frame| frame.try_into().unwrap())
+            .collect();
+
+        Ok(Self {
+            seed,
+            start,
+            program,
+            inputs,
+        })
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], MovieError> {
+        if self.0.len() < len {
+            return Err(MovieError::Truncated);
+        }
+
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], MovieError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    // a length prefixed block of bytes
+    fn block(&mut self) -> Result<&'a [u8], MovieError> {
+        let len = u32::from_le_bytes(self.array()?);
+        self.take(len as usize)
+    }
+}
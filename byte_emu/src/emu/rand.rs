@@ -1,3 +1,7 @@
+use byte_core::bus::Peripheral;
+
+pub const REGISTER_COUNT: u16 = 4;
+
 // from https://matklad.github.io/2023/01/04/on-random-numbers.html
 pub fn random_seed() -> u64 {
     std::hash::Hasher::finish(&std::hash::BuildHasher::build_hasher(
@@ -5,13 +9,61 @@ pub fn random_seed() -> u64 {
     ))
 }
 
-pub fn random_numbers(seed: u32) -> impl Iterator<Item = u32> {
-    let mut random = seed;
+// a xorshift generator, which gives the same numbers for the same seed
+// on every platform. as a peripheral it exposes the seed as four bytes,
+// lowest first, and writing any of them starts over from the new seed.
+#[derive(Debug, Clone, Copy)]
+pub struct Random {
+    seed: u32,
+    state: u32,
+}
 
-    std::iter::repeat_with(move || {
-        random ^= random << 13;
-        random ^= random >> 17;
-        random ^= random << 5;
+impl Random {
+    pub fn new(seed: u32) -> Self {
+        let mut random = Self { seed, state: 0 };
+        random.reseed(seed);
         random
-    })
+    }
+
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    pub fn reseed(&mut self, seed: u32) {
+        self.seed = seed;
+        // xorshift never leaves zero
+        self.state = if seed == 0 { 0x9e37_79b9 } else { seed };
+    }
+
+    // where in the sequence the generator is, for snapshots
+    pub fn state(&self) -> u32 {
+        self.state
+    }
+
+    pub fn set_state(&mut self, state: u32) {
+        self.state = state;
+    }
+}
+
+impl Iterator for Random {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        Some(self.state)
+    }
+}
+
+impl Peripheral for Random {
+    fn peek(&self, addr: u16) -> u8 {
+        self.seed.to_le_bytes()[(addr % REGISTER_COUNT) as usize]
+    }
+
+    fn write(&mut self, addr: u16, byte: u8) {
+        let mut seed = self.seed.to_le_bytes();
+        seed[(addr % REGISTER_COUNT) as usize] = byte;
+        self.reseed(u32::from_le_bytes(seed));
+    }
 }
@@ -0,0 +1,171 @@
+use super::{
+    snapshot::{Snapshot, MEMORY_SIZE},
+    sound::Psg,
+};
+use byte_core::{bus::Timer, cpu::Registers};
+use std::collections::VecDeque;
+
+// only the newest state is kept uncompressed. every entry stores the
+// difference between its memory and the memory of the entry before it,
+// so older states are reconstructed by walking backwards from `latest`.
+// this also makes evicting the oldest entry free, nothing depends on it.
+struct Entry {
+    reg: Registers,
+    cycle: u64,
+    rand: u32,
+    timer: Timer,
+    sound: Psg,
+    delta: Vec<u8>,
+}
+
+pub struct RewindBuffer {
+    entries: VecDeque<Entry>,
+    latest: Box<[u8]>,
+    capacity: usize,
+    max_bytes: usize,
+    bytes: usize,
+}
+
+impl RewindBuffer {
+    pub fn new(capacity: usize, max_bytes: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            latest: vec![0; MEMORY_SIZE].into_boxed_slice(),
+            capacity,
+            max_bytes,
+            bytes: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.latest.fill(0);
+        self.bytes = 0;
+    }
+
+    pub fn push(&mut self, snapshot: &Snapshot) {
+        let delta = encode(&self.latest, &snapshot.memory);
+        self.latest.copy_from_slice(&snapshot.memory);
+
+        self.bytes += delta.len();
+        self.entries.push_back(Entry {
+            reg: snapshot.reg,
+            cycle: snapshot.cycle,
+            rand: snapshot.rand,
+            timer: snapshot.timer.clone(),
+            sound: snapshot.sound.snapshot(),
+            delta,
+        });
+
+        while self.entries.len() > self.capacity
+            || (self.bytes > self.max_bytes && self.entries.len() > 1)
+        {
+            if let Some(entry) = self.entries.pop_front() {
+                self.bytes -= entry.delta.len();
+            }
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<Snapshot> {
+        let entry = self.entries.get(index)?;
+        let mut memory = self.latest.clone();
+
+        self.entries
+            .iter()
+            .skip(index + 1)
+            .rev()
+            .for_each(|entry| decode(&mut memory, &entry.delta));
+
+        Some(Snapshot::new(
+            entry.reg,
+            entry.cycle,
+            entry.rand,
+            entry.timer.clone(),
+            entry.sound.clone(),
+            memory,
+        ))
+    }
+
+    // drops every entry after `index`, making it the newest one
+    pub fn truncate(&mut self, index: usize) -> Option<Snapshot> {
+        let snapshot = self.get(index)?;
+
+        while self.entries.len() > index + 1 {
+            if let Some(entry) = self.entries.pop_back() {
+                self.bytes -= entry.delta.len();
+            }
+        }
+        self.latest.copy_from_slice(&snapshot.memory);
+
+        Some(snapshot)
+    }
+
+    // steps one entry back in time and returns the state to restore
+    pub fn rewind(&mut self) -> Option<Snapshot> {
+        match self.entries.len() {
+            0 => None,
+            1 => self.get(0),
+            n => self.truncate(n - 2),
+        }
+    }
+}
+
+// the delta is `old ^ new` encoded as a list of runs:
+// [skip: u16][len: u16][len bytes], where `skip` counts the unchanged
+// bytes since the end of the previous run.
+fn encode(old: &[u8], new: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut i = 0;
+
+    while i < new.len() {
+        let start = i;
+        while i < new.len() && old[i] == new[i] && i - start < u16::MAX as usize {
+            i += 1;
+        }
+        let skip = i - start;
+
+        let start = i;
+        while i < new.len() && old[i] != new[i] && i - start < u16::MAX as usize {
+            i += 1;
+        }
+        let len = i - start;
+
+        if len == 0 && i == new.len() {
+            break;
+        }
+
+        out.extend_from_slice(&(skip as u16).to_le_bytes());
+        out.extend_from_slice(&(len as u16).to_le_bytes());
+        out.extend((start..i).map(|j| old[j] ^ new[j]));
+    }
+
+    out
+}
+
+fn decode(memory: &mut [u8], delta: &[u8]) {
+    let mut pos = 0;
+    let mut cursor = 0;
+
+    while cursor + 4 <= delta.len() {
+        let skip = u16::from_le_bytes([delta[cursor], delta[cursor + 1]]) as usize;
+        let len = u16::from_le_bytes([delta[cursor + 2], delta[cursor + 3]]) as usize;
+        cursor += 4;
+        pos += skip;
+
+        memory[pos..pos + len]
+            .iter_mut()
+            .zip(&delta[cursor..cursor + len])
+            .for_each(|(m, d)| *m ^= d);
+
+        pos += len;
+        cursor += len;
+    }
+}
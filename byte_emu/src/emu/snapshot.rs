@@ -0,0 +1,37 @@
+use super::sound::Psg;
+use byte_core::{bus::Timer, cpu::Registers};
+
+pub const MEMORY_SIZE: usize = 1 << 16;
+
+#[derive(Clone)]
+pub struct Snapshot {
+    pub reg: Registers,
+    pub cycle: u64,
+    // the state of the random number generator, its seed is in `memory`
+    pub rand: u32,
+    // the counters and latches of the timer aren't in `memory`
+    pub timer: Timer,
+    pub sound: Psg,
+    pub memory: Box<[u8]>,
+}
+
+impl Snapshot {
+    pub fn new(
+        reg: Registers,
+        cycle: u64,
+        rand: u32,
+        timer: Timer,
+        sound: Psg,
+        memory: Box<[u8]>,
+    ) -> Self {
+        assert_eq!(memory.len(), MEMORY_SIZE);
+        Self {
+            reg,
+            cycle,
+            rand,
+            timer,
+            sound,
+            memory,
+        }
+    }
+}
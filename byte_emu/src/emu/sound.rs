@@ -0,0 +1,240 @@
+use super::core::CLOCK_RATE;
+use byte_core::bus::Peripheral;
+use std::{collections::VecDeque, io};
+
+// the synthesizer produces `SAMPLE_RATE` samples for every `CLOCK_RATE`
+// cycles it is clocked with
+pub const SAMPLE_RATE: u32 = 44_100;
+pub const REGISTER_COUNT: u16 = 16;
+
+// a second worth of samples, older ones are dropped if nobody drains them
+const MAX_SAMPLES: usize = SAMPLE_RATE as usize;
+// how often the envelope is ticked, a decay rate of `n` drops the volume
+// by one every `n` ticks
+const ENVELOPE_RATE: u32 = 60;
+const DUTY_CYCLES: [f32; 4] = [0.125, 0.25, 0.5, 0.75];
+
+const GATE: u8 = 0b1000_0000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Waveform {
+    Square,
+    Triangle,
+    Noise,
+}
+
+const WAVEFORMS: [Waveform; 4] = [
+    Waveform::Square,
+    Waveform::Square,
+    Waveform::Triangle,
+    Waveform::Noise,
+];
+
+// every channel has four registers:
+//
+//   +0  frequency in hz, low byte
+//   +1  frequency in hz, high byte
+//   +2  volume in the low nibble, decay rate in the high nibble
+//   +3  bit 7 gates the channel on, bits 0-1 select the duty cycle
+//
+// writing the control register with the gate bit set restarts the
+// envelope at the channel's volume.
+#[derive(Debug, Default, Clone, Copy)]
+struct Channel {
+    registers: [u8; 4],
+    phase: f32,
+    level: u8,
+    // samples left until the envelope ticks again
+    envelope: u32,
+}
+
+impl Channel {
+    fn frequency(&self) -> u16 {
+        u16::from_le_bytes([self.registers[0], self.registers[1]])
+    }
+
+    fn volume(&self) -> u8 {
+        self.registers[2] & 0x0f
+    }
+
+    fn decay(&self) -> u32 {
+        (self.registers[2] >> 4) as u32
+    }
+
+    fn gate(&self) -> bool {
+        self.registers[3] & GATE != 0
+    }
+
+    fn duty(&self) -> f32 {
+        DUTY_CYCLES[(self.registers[3] & 0b11) as usize]
+    }
+
+    fn write(&mut self, reg: usize, byte: u8) {
+        self.registers[reg] = byte;
+
+        if reg == 3 && byte & GATE != 0 {
+            self.level = self.volume();
+            self.envelope = self.envelope_period();
+        }
+    }
+
+    fn envelope_period(&self) -> u32 {
+        self.decay() * SAMPLE_RATE / ENVELOPE_RATE
+    }
+
+    fn tick_envelope(&mut self) {
+        if self.decay() == 0 || self.level == 0 {
+            return;
+        }
+
+        self.envelope = self.envelope.saturating_sub(1);
+        if self.envelope == 0 {
+            self.level -= 1;
+            self.envelope = self.envelope_period();
+        }
+    }
+
+    // advances the channel by a sample, returns whether the phase wrapped
+    fn advance(&mut self) -> bool {
+        self.tick_envelope();
+
+        self.phase += self.frequency() as f32 / SAMPLE_RATE as f32;
+        let wrapped = self.phase >= 1.0;
+        self.phase = self.phase.fract();
+        wrapped
+    }
+}
+
+// a small programmable sound generator with two square wave channels,
+// a triangle wave channel and a noise channel
+#[derive(Clone)]
+pub struct Psg {
+    channels: [Channel; 4],
+    // 15-bit linear feedback shift register driving the noise channel
+    noise: u16,
+    // cycles that haven't added up to a whole sample yet, in units of
+    // `1 / SAMPLE_RATE` cycles
+    remainder: u64,
+    samples: VecDeque<f32>,
+}
+
+impl Default for Psg {
+    fn default() -> Self {
+        Self {
+            channels: [Channel::default(); 4],
+            noise: 1,
+            remainder: 0,
+            samples: VecDeque::with_capacity(MAX_SAMPLES),
+        }
+    }
+}
+
+impl Psg {
+    // a copy of the synthesizer for snapshots, without the samples that are
+    // waiting to be taken
+    pub fn snapshot(&self) -> Psg {
+        Psg {
+            samples: VecDeque::new(),
+            ..self.clone()
+        }
+    }
+
+    // picks up where `snapshot` left off, the samples that are waiting to
+    // be taken have already been played
+    pub fn restore(&mut self, snapshot: &Psg) {
+        self.channels = snapshot.channels;
+        self.noise = snapshot.noise;
+        self.remainder = snapshot.remainder;
+    }
+
+    // lets `cycles` cpu cycles worth of time pass
+    pub fn clock(&mut self, cycles: u64) {
+        self.remainder += cycles * SAMPLE_RATE as u64;
+
+        while self.remainder >= CLOCK_RATE {
+            self.remainder -= CLOCK_RATE;
+
+            let sample = self.sample();
+            if self.samples.len() == MAX_SAMPLES {
+                self.samples.pop_front();
+            }
+            self.samples.push_back(sample);
+        }
+    }
+
+    // the samples produced since the last call, in the range [-1, 1]
+    pub fn take_samples(&mut self) -> Vec<f32> {
+        self.samples.drain(..).collect()
+    }
+
+    fn sample(&mut self) -> f32 {
+        let mut mix = 0.0;
+
+        for (channel, waveform) in self.channels.iter_mut().zip(WAVEFORMS) {
+            let wrapped = channel.advance();
+            if !channel.gate() || channel.frequency() == 0 {
+                continue;
+            }
+
+            let value = match waveform {
+                Waveform::Square if channel.phase < channel.duty() => 1.0,
+                Waveform::Square => -1.0,
+                Waveform::Triangle => 4.0 * (channel.phase - 0.5).abs() - 1.0,
+                Waveform::Noise => {
+                    if wrapped {
+                        let bit = (self.noise ^ (self.noise >> 1)) & 1;
+                        self.noise = (self.noise >> 1) | (bit << 14);
+                    }
+                    if self.noise & 1 != 0 {
+                        1.0
+                    } else {
+                        -1.0
+                    }
+                }
+            };
+
+            mix += value * channel.level as f32 / 15.0;
+        }
+
+        mix / self.channels.len() as f32
+    }
+}
+
+impl Peripheral for Psg {
+    fn peek(&self, addr: u16) -> u8 {
+        let reg = addr as usize % REGISTER_COUNT as usize;
+        self.channels[reg / 4].registers[reg % 4]
+    }
+
+    fn write(&mut self, addr: u16, byte: u8) {
+        let reg = addr as usize % REGISTER_COUNT as usize;
+        self.channels[reg / 4].write(reg % 4, byte);
+    }
+}
+
+// writes mono 16-bit pcm samples as a wav file
+pub fn write_wav(mut writer: impl io::Write, samples: &[f32]) -> io::Result<()> {
+    let data_size = samples.len() as u32 * 2;
+
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&(36 + data_size).to_le_bytes())?;
+    writer.write_all(b"WAVEfmt ")?;
+    writer.write_all(&16u32.to_le_bytes())?;
+    // pcm, one channel
+    writer.write_all(&1u16.to_le_bytes())?;
+    writer.write_all(&1u16.to_le_bytes())?;
+    writer.write_all(&SAMPLE_RATE.to_le_bytes())?;
+    writer.write_all(&(SAMPLE_RATE * 2).to_le_bytes())?;
+    // block align, bits per sample
+    writer.write_all(&2u16.to_le_bytes())?;
+    writer.write_all(&16u16.to_le_bytes())?;
+    writer.write_all(b"data")?;
+    writer.write_all(&data_size.to_le_bytes())?;
+
+    for sample in samples {
+        let sample = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        writer.write_all(&sample.to_le_bytes())?;
+    }
+
+    Ok(())
+}
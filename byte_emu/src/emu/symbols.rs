@@ -0,0 +1,69 @@
+use super::parse_addr;
+use std::collections::{BTreeMap, HashMap};
+
+// label <-> address table loaded from a symbol file. every line holds a
+// single symbol in one of the following forms:
+//
+//   reset = $8000
+//   reset EQU $8000
+//   $8000 reset
+//   al C:8000 .reset   (VICE label files)
+#[derive(Default)]
+pub struct Symbols {
+    labels: BTreeMap<u16, String>,
+    addrs: HashMap<String, u16>,
+}
+
+impl Symbols {
+    pub fn parse(source: &str) -> Self {
+        let mut symbols = Self::default();
+
+        source
+            .lines()
+            .map(|line| line.split(';').next().unwrap_or_default())
+            .filter_map(parse_line)
+            .for_each(|(label, addr)| symbols.insert(label, addr));
+
+        symbols
+    }
+
+    pub fn insert(&mut self, label: &str, addr: u16) {
+        self.labels.entry(addr).or_insert_with(|| label.to_owned());
+        self.addrs.insert(label.to_owned(), addr);
+    }
+
+    pub fn label(&self, addr: u16) -> Option<&str> {
+        self.labels.get(&addr).map(|label| label.as_str())
+    }
+
+    pub fn addr(&self, label: &str) -> Option<u16> {
+        self.addrs.get(label).copied()
+    }
+}
+
+fn parse_line(line: &str) -> Option<(&str, u16)> {
+    let tokens: Vec<&str> = line
+        .split(|c: char| c.is_whitespace() || c == '=')
+        .filter(|token| !token.is_empty())
+        .collect();
+
+    match tokens.as_slice() {
+        ["al", addr, label] => Some((
+            label.trim_start_matches('.'),
+            parse_addr(addr.trim_start_matches("C:"))?,
+        )),
+        [label, "EQU" | "equ", addr] if is_label(label) => Some((label, parse_addr(addr)?)),
+        // `C000` is a valid label too, so `C000 reset` only parses the other
+        // way around
+        [first, second] => [(first, second), (second, first)]
+            .into_iter()
+            .filter(|(label, _)| is_label(label))
+            .find_map(|(label, addr)| Some((*label, parse_addr(addr)?))),
+        _ => None,
+    }
+}
+
+fn is_label(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
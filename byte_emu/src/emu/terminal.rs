@@ -0,0 +1,24 @@
+use byte_core::bus::Peripheral;
+
+// a character output port, every byte the program writes to it is kept
+// until someone takes it. reading it returns 0.
+#[derive(Debug, Default)]
+pub struct Terminal {
+    output: Vec<u8>,
+}
+
+impl Terminal {
+    pub fn take_output(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.output)
+    }
+}
+
+impl Peripheral for Terminal {
+    fn peek(&self, _addr: u16) -> u8 {
+        0
+    }
+
+    fn write(&mut self, _addr: u16, byte: u8) {
+        self.output.push(byte);
+    }
+}
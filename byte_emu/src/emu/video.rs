@@ -0,0 +1,252 @@
+use super::font::{self, GLYPH_SIZE};
+
+pub const MAX_WIDTH: usize = 128;
+
+// a frame is scanned out line by line, the screen covers the first
+// `VISIBLE_LINES` of them and the rest are the vertical blank. the
+// 64x64 resolution shows every line twice.
+pub const VISIBLE_LINES: usize = 128;
+pub const LINES_PER_FRAME: usize = 160;
+
+// offsets of the video registers from the start of their block in the
+// i/o page
+const MODE: u16 = 0;
+const TILE_MAP: u16 = 1;
+const PATTERNS: u16 = 2;
+const SCROLL_X: u16 = 3;
+const SCROLL_Y: u16 = 4;
+const SPRITES: u16 = 5;
+const RESOLUTION: u16 = 6;
+// the scanline being drawn, and the one that raises an interrupt when
+// it's enabled in `CONTROL`
+pub const LINE: u16 = 7;
+pub const LINE_COMPARE: u16 = 8;
+pub const CONTROL: u16 = 9;
+pub const STATUS: u16 = 10;
+
+pub const CONTROL_LINE_IRQ: u8 = 0b0000_0001;
+// set while the vertical blank lasts
+pub const STATUS_VBLANK: u8 = 0b1000_0000;
+// set when the scanline interrupt fires, programs clear it themselves
+pub const STATUS_LINE: u8 = 0b0100_0000;
+// 16 colours of three bytes each, red, green and blue
+pub const PALETTE: u16 = 0x10;
+pub const REGISTER_COUNT: u16 = PALETTE + 16 * 3;
+
+pub const DEFAULT_PALETTE: [u32; 16] = [
+    0x000000FF, 0xFFFFFFFF, 0x880000FF, 0xAAFFEEFF, 0xCC44CCFF, 0x00CC55FF, 0x0000AAFF, 0xEEEE77FF,
+    0x664400FF, 0xFF7777FF, 0x333333FF, 0x777777FF, 0xAAFF66FF, 0x0088FFFF, 0xDD8855FF, 0xBBBBBBFF,
+];
+
+const TILE_SIZE: usize = 8;
+const TILE_BYTES: u16 = 32;
+
+const SPRITE_COUNT: u16 = 8;
+const SPRITE_ENABLED: u8 = 0b1000_0000;
+const SPRITE_BEHIND: u8 = 0b0000_0100;
+const SPRITE_FLIP_Y: u8 = 0b0000_0010;
+const SPRITE_FLIP_X: u8 = 0b0000_0001;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    // a byte per pixel at the page selected by the video page register
+    Bitmap,
+    // like `Bitmap`, but with two pixels to a byte, the left one in the
+    // high nibble
+    Packed,
+    // a map of 8x8 tiles, indexing into the pattern table
+    Tiles,
+    // characters of the built-in 4x4 font at the tile map's page, set
+    // pixels use colour 1 and the rest colour 0
+    Text,
+}
+
+// characters with the high bit set are drawn in inverse
+const TEXT_INVERSE: u8 = 0b1000_0000;
+
+// a rendered frame, in rgba
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u32>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0x000000ff; width * height],
+        }
+    }
+}
+
+// the state of the video registers, read once per scanline
+#[derive(Debug, Clone, Copy)]
+pub struct Video {
+    mode: Mode,
+    // the screen is square, either 64 or 128 pixels wide
+    size: usize,
+    bitmap: u16,
+    tile_map: u16,
+    patterns: u16,
+    scroll: (u8, u8),
+    sprites: u16,
+    palette: [u32; 16],
+}
+
+impl Video {
+    // `page` is the video page register, `base` the address of the first
+    // register in the i/o page
+    pub fn read(peek: impl Fn(u16) -> u8, page: u8, base: u16) -> Self {
+        let reg = |offset| peek(base + offset);
+
+        let mut palette = [0; 16];
+        palette.iter_mut().enumerate().for_each(|(i, color)| {
+            let rgb = |c| reg(PALETTE + i as u16 * 3 + c) as u32;
+            *color = rgb(0) << 24 | rgb(1) << 16 | rgb(2) << 8 | 0xff;
+        });
+
+        Self {
+            mode: match reg(MODE) & 0b11 {
+                1 => Mode::Tiles,
+                2 => Mode::Packed,
+                3 => Mode::Text,
+                _ => Mode::Bitmap,
+            },
+            size: if reg(RESOLUTION) & 1 != 0 { 128 } else { 64 },
+            bitmap: (page as u16 & 0xf) << 12,
+            tile_map: (reg(TILE_MAP) as u16) << 8,
+            patterns: (reg(PATTERNS) as u16) << 8,
+            scroll: (reg(SCROLL_X), reg(SCROLL_Y)),
+            sprites: (reg(SPRITES) as u16) << 8,
+            palette,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.size
+    }
+
+    pub fn height(&self) -> usize {
+        self.size
+    }
+
+    pub fn color(&self, index: u8) -> u32 {
+        self.palette[index as usize & 0xf]
+    }
+
+    // renders the palette indices of a single line, `line` has to be
+    // `width` pixels long
+    pub fn render_line(&self, peek: impl Fn(u16) -> u8, y: usize, line: &mut [u8]) {
+        let size = self.size;
+
+        match self.mode {
+            Mode::Bitmap => line.iter_mut().enumerate().for_each(|(x, pixel)| {
+                let offset = (y * size + x) as u16;
+                *pixel = peek(self.bitmap.wrapping_add(offset)) & 0xf;
+            }),
+            Mode::Packed => line.iter_mut().enumerate().for_each(|(x, pixel)| {
+                let offset = ((y * size + x) / 2) as u16;
+                *pixel = nibble(peek(self.bitmap.wrapping_add(offset)), x);
+            }),
+            Mode::Tiles => {
+                let y = (y + self.scroll.1 as usize) % size;
+                let map_width = size / TILE_SIZE;
+
+                line.iter_mut().enumerate().for_each(|(x, pixel)| {
+                    let x = (x + self.scroll.0 as usize) % size;
+                    let offset = (y / TILE_SIZE) * map_width + x / TILE_SIZE;
+                    let tile = peek(self.tile_map.wrapping_add(offset as u16));
+                    *pixel = self.pattern(&peek, tile, x % TILE_SIZE, y % TILE_SIZE);
+                });
+            }
+            Mode::Text => {
+                let columns = size / GLYPH_SIZE;
+
+                line.iter_mut().enumerate().for_each(|(x, pixel)| {
+                    let offset = (y / GLYPH_SIZE) * columns + x / GLYPH_SIZE;
+                    let c = peek(self.tile_map.wrapping_add(offset as u16));
+                    let set = font::pixel(font::glyph(c), x % GLYPH_SIZE, y % GLYPH_SIZE);
+                    *pixel = (set != (c & TEXT_INVERSE != 0)) as u8;
+                });
+            }
+        }
+
+        self.render_sprites(&peek, y, line);
+    }
+
+    // like `render_line`, with the colours looked up in the palette
+    pub fn render_colors(&self, peek: impl Fn(u16) -> u8, y: usize, line: &mut [u32]) {
+        let mut indices = [0; MAX_WIDTH];
+        let indices = &mut indices[..line.len()];
+        self.render_line(peek, y, indices);
+
+        line.iter_mut()
+            .zip(indices.iter())
+            .for_each(|(color, index)| *color = self.color(*index));
+    }
+
+    // sprites are 8x8 tiles from the pattern table with four bytes of
+    // attributes each: x, y, tile and flags. colour 0 is transparent and
+    // lower numbered sprites are drawn on top of the higher ones.
+    fn render_sprites(&self, peek: &impl Fn(u16) -> u8, y: usize, line: &mut [u8]) {
+        let mut drawn = [false; MAX_WIDTH];
+
+        for i in 0..SPRITE_COUNT {
+            let attr = |offset| peek(self.sprites + i * 4 + offset);
+            let flags = attr(3);
+            if flags & SPRITE_ENABLED == 0 {
+                continue;
+            }
+
+            let row = (y as u8).wrapping_sub(attr(1)) as usize;
+            if row >= TILE_SIZE {
+                continue;
+            }
+            let row = if flags & SPRITE_FLIP_Y != 0 {
+                TILE_SIZE - 1 - row
+            } else {
+                row
+            };
+
+            for col in 0..TILE_SIZE {
+                let x = attr(0) as usize + col;
+                if x >= line.len() || drawn[x] {
+                    continue;
+                }
+
+                let col = if flags & SPRITE_FLIP_X != 0 {
+                    TILE_SIZE - 1 - col
+                } else {
+                    col
+                };
+                let color = self.pattern(peek, attr(2), col, row);
+                if color == 0 {
+                    continue;
+                }
+
+                drawn[x] = true;
+                if flags & SPRITE_BEHIND == 0 || line[x] == 0 {
+                    line[x] = color;
+                }
+            }
+        }
+    }
+
+    // tiles are stored two pixels to a byte, the left one in the high nibble
+    fn pattern(&self, peek: &impl Fn(u16) -> u8, tile: u8, x: usize, y: usize) -> u8 {
+        let offset = tile as u16 * TILE_BYTES + (y * TILE_SIZE + x) as u16 / 2;
+        nibble(peek(self.patterns.wrapping_add(offset)), x)
+    }
+}
+
+// the pixel at `x` of a byte holding two of them
+fn nibble(byte: u8, x: usize) -> u8 {
+    if x & 1 == 0 {
+        byte >> 4
+    } else {
+        byte & 0xf
+    }
+}
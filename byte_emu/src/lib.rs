@@ -0,0 +1,13 @@
+#![warn(clippy::all, rust_2018_idioms)]
+
+#[cfg(feature = "gui")]
+mod app;
+pub mod emu;
+
+#[cfg(feature = "gui")]
+pub use app::ByteEmuApp;
+
+#[cfg(feature = "gui")]
+const DEFAULT_BINARY: &[u8; 1 << 16] = include_bytes!("../assets/demo.bin");
+#[cfg(feature = "gui")]
+const DEFAULT_SOURCE: &str = include_str!("../assets/demo.s");
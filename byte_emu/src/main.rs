@@ -1,11 +1,7 @@
 #![warn(clippy::all, rust_2018_idioms)]
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod app;
-mod emu;
-
-const DEFAULT_BINARY: &[u8; 1 << 16] = include_bytes!("../assets/demo.bin");
-const DEFAULT_SOURCE: &str = include_str!("../assets/demo.s");
+use byte_emu::ByteEmuApp;
 
 #[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result<()> {
@@ -15,6 +11,20 @@ fn main() -> eframe::Result<()> {
 
     env_logger::init();
 
+    // byte_emu [--seed <n>] [program]
+    let mut path = None;
+    let mut seed = None;
+    let mut args = args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let value = args.next().expect("`--seed` expects a value");
+                seed = Some(value.parse().expect("the seed has to be a number"));
+            }
+            _ => path = Some(arg),
+        }
+    }
+
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([400.0, 300.0])
@@ -25,7 +35,7 @@ fn main() -> eframe::Result<()> {
         "byte-emu",
         native_options,
         Box::new(|cc| {
-            let program = match args().nth(1) {
+            let program = match path {
                 Some(path) => {
                     let mut data = Vec::new();
                     let mut file = File::open(path).expect("failed to open the file");
@@ -37,7 +47,7 @@ fn main() -> eframe::Result<()> {
                 None => None,
             };
 
-            Ok(Box::new(app::ByteEmuApp::new(cc, program)))
+            Ok(Box::new(ByteEmuApp::new(cc, program, seed)))
         }),
     )
 }
@@ -65,7 +75,7 @@ fn main() {
             .start(
                 canvas,
                 web_options,
-                Box::new(|cc| Ok(Box::new(app::ByteEmuApp::new(cc, None)))),
+                Box::new(|cc| Ok(Box::new(ByteEmuApp::new(cc, None, None)))),
             )
             .await;
 
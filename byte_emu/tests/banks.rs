@@ -0,0 +1,35 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+use byte_emu::emu::core::ByteEmu;
+
+#[test]
+fn big_images_boot_with_bank_2_selected() {
+    // JMP $8000 in bank 2, where the reset vector points to
+    let mut program = vec![0; 0x20000];
+    program[0x8000..0x8003].copy_from_slice(&[0x4c, 0x00, 0x80]);
+    program[0xfffc..0xfffe].copy_from_slice(&[0x00, 0x80]);
+    // the image's byte at the bank register isn't a selection
+    program[0x00fb] = 5;
+
+    let mut emu = ByteEmu::default();
+    emu.load_program(&program, 0x0000);
+
+    assert_eq!(emu.peek(0x00fb), 2);
+    assert_eq!(emu.peek(0x8000), 0x4c);
+    assert_eq!(emu.pc(), 0x8000);
+}
+
+#[test]
+fn banks_can_be_switched() {
+    let mut program = vec![0; 0x20000];
+    program[0x8000..0x8003].copy_from_slice(&[0x4c, 0x00, 0x80]);
+    program[0xfffc..0xfffe].copy_from_slice(&[0x00, 0x80]);
+    program[0x14000] = 0xaa;
+
+    let mut emu = ByteEmu::default();
+    emu.load_program(&program, 0x0000);
+    emu.poke(0x00fb, 5);
+
+    assert_eq!(emu.peek(0x00fb), 5);
+    assert_eq!(emu.peek(0x8000), 0xaa);
+}
@@ -0,0 +1,95 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+use byte_emu::emu::{
+    core::{ByteEmu, ByteInputState},
+    input::{gamepad_state, Gamepads, PadButton},
+    movie::{Movie, MovieError},
+};
+
+const NONE: ByteInputState = ByteInputState::empty();
+
+// JMP $8000 forever
+fn idle() -> Vec<u8> {
+    let mut program = vec![0; 0x10000];
+    program[0x8000..0x8003].copy_from_slice(&[0x4c, 0x00, 0x80]);
+    program[0xfffc..0xfffe].copy_from_slice(&[0x00, 0x80]);
+    program
+}
+
+struct MockGamepads(Vec<Vec<PadButton>>);
+
+impl Gamepads for MockGamepads {
+    fn poll(&mut self) -> Vec<Vec<PadButton>> {
+        self.0.clone()
+    }
+}
+
+#[test]
+fn gamepads_are_players_in_order() {
+    let mut gamepads = MockGamepads(vec![
+        vec![PadButton::Up, PadButton::South, PadButton::Start],
+        vec![PadButton::East, PadButton::North],
+        // a third gamepad has nowhere to go
+        vec![PadButton::Left],
+    ]);
+
+    assert_eq!(gamepad_state(&mut gamepads), [
+        ByteInputState::UP | ByteInputState::B | ByteInputState::START,
+        ByteInputState::A,
+    ]);
+    assert_eq!(gamepad_state(&mut MockGamepads(Vec::new())), [NONE, NONE]);
+}
+
+#[test]
+fn players_have_their_own_ports() {
+    let mut emu = ByteEmu::default();
+    emu.load_program(&idle(), 0x0000);
+    emu.step([ByteInputState::RIGHT, ByteInputState::A | ByteInputState::LEFT]);
+
+    assert_eq!(emu.peek(0x00ff), 0x01);
+    assert_eq!(emu.peek(0x7f68), 0x01);
+    assert_eq!(emu.peek(0x7f69), 0x82);
+}
+
+#[test]
+fn movies_replay_both_players() {
+    let inputs = [
+        [ByteInputState::UP, NONE],
+        [ByteInputState::A, ByteInputState::DOWN],
+        [NONE, ByteInputState::START],
+    ];
+
+    let mut emu = ByteEmu::default();
+    emu.load_program(&idle(), 0x0000);
+    emu.record_movie();
+    inputs.iter().for_each(|input| { emu.step(*input); });
+    let movie = emu.stop_movie().unwrap();
+    assert_eq!(Movie::from_bytes(&movie.to_bytes()), Ok(movie.clone()));
+
+    emu.play_movie(movie);
+    for input in inputs {
+        emu.step([NONE, NONE]);
+        assert_eq!([emu.peek(0x7f68), emu.peek(0x7f69)], input.map(|state| state.bits()));
+    }
+}
+
+#[test]
+fn movie_format() {
+    let mut bytes = b"BYTM\x01".to_vec();
+    bytes.extend([0x2a, 0, 0, 0, 0x00, 0x00]);
+    bytes.extend([1, 0, 0, 0, 0xea]);
+    bytes.extend([4, 0, 0, 0, 0x08, 0x00, 0x80, 0x01]);
+
+    let movie = Movie::from_bytes(&bytes).unwrap();
+    assert_eq!((movie.seed, movie.program.as_slice()), (0x2a, [0xea].as_slice()));
+    assert_eq!(movie.inputs, [[0x08, 0x00], [0x80, 0x01]]);
+    assert_eq!(movie.to_bytes(), bytes);
+
+    // a frame without the input of player 2
+    bytes[16] = 3;
+    bytes.pop();
+    assert_eq!(Movie::from_bytes(&bytes), Err(MovieError::Truncated));
+
+    bytes[4] = 2;
+    assert_eq!(Movie::from_bytes(&bytes), Err(MovieError::UnsupportedVersion(2)));
+}
@@ -0,0 +1,41 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+use byte_emu::emu::{core::ByteInputState, input::KeyBindings};
+use std::collections::HashSet;
+
+const NONE: ByteInputState = ByteInputState::empty();
+
+fn keys(keys: &[egui::Key]) -> HashSet<egui::Key> {
+    keys.iter().copied().collect()
+}
+
+#[test]
+fn default_bindings() {
+    let bindings = KeyBindings::default();
+
+    let state = bindings.state(&keys(&[egui::Key::ArrowUp, egui::Key::D, egui::Key::J]));
+    assert_eq!(state, [ByteInputState::UP | ByteInputState::A, ByteInputState::LEFT]);
+    assert_eq!(bindings.state(&keys(&[egui::Key::Z])), [NONE, NONE]);
+}
+
+#[test]
+fn buttons_can_have_several_keys() {
+    let mut bindings = KeyBindings::default();
+    bindings.bind(0, ByteInputState::A, egui::Key::Space);
+
+    assert_eq!(bindings.keys(0, ByteInputState::A), [egui::Key::D, egui::Key::Space]);
+    assert_eq!(bindings.state(&keys(&[egui::Key::Space]))[0], ByteInputState::A);
+    assert_eq!(bindings.state(&keys(&[egui::Key::D]))[0], ByteInputState::A);
+
+    bindings.unbind(0, ByteInputState::A, egui::Key::D);
+    assert_eq!(bindings.state(&keys(&[egui::Key::D]))[0], NONE);
+}
+
+#[test]
+fn binding_a_key_moves_it() {
+    let mut bindings = KeyBindings::default();
+    bindings.bind(1, ByteInputState::START, egui::Key::ArrowUp);
+
+    assert!(bindings.keys(0, ByteInputState::UP).is_empty());
+    assert_eq!(bindings.state(&keys(&[egui::Key::ArrowUp])), [NONE, ByteInputState::START]);
+}
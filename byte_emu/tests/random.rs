@@ -0,0 +1,46 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+use byte_core::debugger::{Access, Breakpoint, Watchpoint};
+use byte_emu::emu::core::{ByteEmu, ByteInputState};
+
+// copies 256 numbers from the random register to $0200
+fn program() -> Vec<u8> {
+    let mut program = vec![0; 0x10000];
+    program[0x8000..0x800f].copy_from_slice(&[
+        0x78,             // SEI
+        0xa6, 0x10,       // LDX $10
+        0xa5, 0xfe,       // LDA $FE
+        0x9d, 0x00, 0x02, // STA $0200,X
+        0xe6, 0x10,       // INC $10
+        0xd0, 0xf5,       // BNE $8001
+        0x4c, 0x0c, 0x80, // JMP $800C
+    ]);
+    program[0xfffc..0xfffe].copy_from_slice(&[0x00, 0x80]);
+    program
+}
+
+// runs a frame, resuming whenever the debugger stops it
+fn numbers(emu: &mut ByteEmu) -> (Vec<u8>, usize) {
+    emu.set_seed(1234);
+    emu.load_program(&program(), 0x0000);
+
+    let mut stops = 0;
+    while emu.step([ByteInputState::empty(); 2]).is_some() {
+        stops += 1;
+        emu.resume();
+    }
+
+    ((0x0200..0x0300).map(|addr| emu.peek(addr)).collect(), stops)
+}
+
+#[test]
+fn stopping_does_not_change_the_numbers() {
+    let (expected, stops) = numbers(&mut ByteEmu::default());
+    assert_eq!(stops, 0);
+    assert!(expected.windows(2).any(|pair| pair[0] != pair[1]));
+
+    let mut emu = ByteEmu::default();
+    emu.debugger().add_breakpoint(Breakpoint::new(0x8003));
+    emu.debugger().add_watchpoint(Watchpoint::new(0x0200, 0x02ff, Access::WRITE));
+    assert_eq!(numbers(&mut emu), (expected, 512));
+}
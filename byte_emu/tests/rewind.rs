@@ -0,0 +1,184 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+use byte_core::{bus::Timer, cpu::Registers};
+use byte_emu::emu::{
+    core::{ByteEmu, ByteInputState},
+    rewind::RewindBuffer,
+    snapshot::{Snapshot, MEMORY_SIZE},
+    sound::Psg,
+};
+
+const NONE: [ByteInputState; 2] = [ByteInputState::empty(); 2];
+const TIMER: u16 = 0x7f50;
+
+// SEI, then JMP $8001 forever
+fn idle() -> ByteEmu {
+    let mut program = vec![0; 0x10000];
+    program[0x8000..0x8004].copy_from_slice(&[0x78, 0x4c, 0x01, 0x80]);
+    program[0xfffc..0xfffe].copy_from_slice(&[0x00, 0x80]);
+
+    let mut emu = ByteEmu::default();
+    emu.load_program(&program, 0x0000);
+    emu
+}
+
+fn timer(emu: &ByteEmu) -> Vec<u8> {
+    (0..16).map(|reg| emu.peek(TIMER + reg)).collect()
+}
+
+// a free-running timer 1 with its interrupt enabled, which fires a few
+// times every frame
+fn start_timer(emu: &mut ByteEmu) {
+    emu.poke(TIMER + 0xb, 0x40);
+    emu.poke(TIMER + 0xe, 0xc0);
+    emu.poke(TIMER + 0x4, 0x34);
+    emu.poke(TIMER + 0x5, 0x12);
+}
+
+#[test]
+fn restoring_leaves_the_timer_alone() {
+    let mut emu = idle();
+    start_timer(&mut emu);
+    emu.step(NONE);
+
+    let before = timer(&emu);
+    assert_eq!(before[0xd], 0xc0);
+
+    let snapshot = emu.snapshot();
+    emu.restore(&snapshot);
+    assert_eq!(timer(&emu), before);
+}
+
+#[test]
+fn rewinding_restores_a_running_timer() {
+    let mut emu = idle();
+    start_timer(&mut emu);
+    emu.step(NONE);
+    emu.step(NONE);
+
+    let position = emu.rewind_position();
+    let before = timer(&emu);
+
+    // acknowledge the interrupt and let the counter run on
+    emu.poke(TIMER + 0xd, 0x40);
+    emu.step(NONE);
+    emu.step(NONE);
+    emu.poke(TIMER + 0xd, 0x40);
+    assert_ne!(timer(&emu), before);
+
+    emu.seek(position);
+    assert_eq!(timer(&emu), before);
+}
+
+// channel 0 playing a 50% square wave that decays
+fn start_sound(emu: &mut ByteEmu) {
+    emu.poke(0x7f00, 0xb9);
+    emu.poke(0x7f01, 0x01);
+    emu.poke(0x7f02, 0x1f);
+    emu.poke(0x7f03, 0x82);
+}
+
+#[test]
+fn rewinding_restores_the_sound() {
+    let mut emu = idle();
+    start_sound(&mut emu);
+    emu.step(NONE);
+    emu.take_samples();
+
+    let snapshot = emu.snapshot();
+    emu.step(NONE);
+    let samples = emu.take_samples();
+    assert!(!samples.is_empty());
+
+    emu.restore(&snapshot);
+    emu.step(NONE);
+    assert_eq!(emu.take_samples(), samples);
+}
+
+#[test]
+fn loading_a_program_silences_the_sound() {
+    let mut emu = idle();
+    start_sound(&mut emu);
+    emu.step(NONE);
+
+    emu.load_program(&emu.memory(), 0x0000);
+    emu.step(NONE);
+    assert_eq!(emu.peek(0x7f03), 0);
+    assert!(emu.take_samples().iter().all(|sample| *sample == 0.0));
+}
+
+fn snapshot(cycle: u64, memory: Vec<u8>) -> Snapshot {
+    Snapshot::new(Registers::default(), cycle, 0, Timer::default(), Psg::default(), memory.into())
+}
+
+// a different pattern of memory for every `n`
+fn memory(n: u8) -> Vec<u8> {
+    (0..MEMORY_SIZE).map(|i| if i % (n as usize + 2) == 0 { n } else { (i >> 8) as u8 }).collect()
+}
+
+#[test]
+fn deltas_round_trip() {
+    let memories = [
+        memory(0),
+        memory(1),
+        // a run of changes longer than a single delta run can hold
+        memory(1).iter().map(|byte| !byte).collect(),
+        vec![0; MEMORY_SIZE],
+        memory(3),
+        memory(3),
+    ];
+
+    let mut buffer = RewindBuffer::new(10, usize::MAX);
+    memories.iter().enumerate().for_each(|(i, memory)| buffer.push(&snapshot(i as u64, memory.clone())));
+
+    assert_eq!(buffer.len(), memories.len());
+    for (i, memory) in memories.iter().enumerate() {
+        let snapshot = buffer.get(i).unwrap();
+        assert_eq!(snapshot.cycle, i as u64);
+        assert!(*snapshot.memory == **memory, "entry {i}");
+    }
+}
+
+#[test]
+fn oldest_entries_are_dropped() {
+    let mut buffer = RewindBuffer::new(3, usize::MAX);
+    (0..5).for_each(|i| buffer.push(&snapshot(i, memory(i as u8))));
+
+    assert_eq!(buffer.len(), 3);
+    for i in 0..3 {
+        let snapshot = buffer.get(i).unwrap();
+        assert_eq!(snapshot.cycle, i as u64 + 2);
+        assert!(*snapshot.memory == *memory(i as u8 + 2));
+    }
+    assert!(buffer.get(3).is_none());
+}
+
+#[test]
+fn deltas_start_over_after_clearing() {
+    // only a few bytes of deltas fit, the newest entry is always kept
+    let mut buffer = RewindBuffer::new(10, 16);
+    buffer.push(&snapshot(0, vec![0xff; MEMORY_SIZE]));
+    buffer.clear();
+
+    // empty memory doesn't differ from a cleared buffer, so neither
+    // entry takes up any space
+    buffer.push(&snapshot(1, vec![0; MEMORY_SIZE]));
+    buffer.push(&snapshot(2, vec![0; MEMORY_SIZE]));
+    assert_eq!(buffer.len(), 2);
+    assert_eq!(buffer.get(0).unwrap().cycle, 1);
+}
+
+#[test]
+fn stepping_after_seeking_drops_the_later_frames() {
+    let mut emu = idle();
+    (0..5).for_each(|_| { emu.step(NONE); });
+    assert_eq!(emu.rewind_len(), 5);
+
+    let cycle = emu.cycle();
+    emu.seek(1);
+    assert_eq!((emu.rewind_position(), emu.rewind_len()), (1, 5));
+    assert!(emu.cycle() < cycle);
+
+    emu.step(NONE);
+    assert_eq!((emu.rewind_position(), emu.rewind_len()), (2, 3));
+}
@@ -0,0 +1,107 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+use byte_core::bus::Peripheral;
+use byte_emu::emu::{
+    core::CLOCK_RATE,
+    sound::{write_wav, Psg, SAMPLE_RATE},
+};
+
+// the cycles it takes to produce `n` samples
+fn cycles(n: u64) -> u64 {
+    (n * CLOCK_RATE).div_ceil(SAMPLE_RATE as u64)
+}
+
+// channel 0 as a 50% square wave
+fn square(frequency: u16, volume_decay: u8) -> Psg {
+    let mut psg = Psg::default();
+    let [lo, hi] = frequency.to_le_bytes();
+    psg.write(0, lo);
+    psg.write(1, hi);
+    psg.write(2, volume_decay);
+    psg.write(3, 0x82);
+    psg
+}
+
+#[test]
+fn clock_produces_samples_at_the_sample_rate() {
+    let mut psg = Psg::default();
+    psg.clock(CLOCK_RATE);
+    assert_eq!(psg.take_samples().len(), SAMPLE_RATE as usize);
+
+    // leftover cycles carry over to the next call
+    psg.clock(cycles(1) - 1);
+    assert!(psg.take_samples().is_empty());
+    psg.clock(1);
+    assert_eq!(psg.take_samples().len(), 1);
+}
+
+#[test]
+fn square_wave_period() {
+    // 441 hz is a period of 100 samples
+    let mut psg = square(441, 0x0f);
+    psg.clock(CLOCK_RATE);
+    let samples = psg.take_samples();
+
+    let rising = samples
+        .windows(2)
+        .enumerate()
+        .filter(|(_, pair)| pair[0] < 0.0 && pair[1] > 0.0)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    assert!((440..=441).contains(&rising.len()));
+    assert!(rising.windows(2).all(|pair| (99..=101).contains(&(pair[1] - pair[0]))));
+
+    // every channel gets a quarter of the mix
+    assert!(samples.iter().all(|sample| sample.abs() == 0.25));
+}
+
+#[test]
+fn envelope_decays_the_volume() {
+    // a decay of 1 lowers the volume every 60th of a second
+    let mut psg = square(441, 0x1f);
+    psg.clock(cycles(SAMPLE_RATE as u64 / 60 * 15));
+    let samples = psg.take_samples();
+    let level = |i: usize| samples[i].abs() * 60.0;
+
+    assert_eq!(level(0), 15.0);
+    assert_eq!(level(733), 15.0);
+    assert_eq!(level(734), 14.0);
+    assert_eq!(level(735 * 14 - 1), 1.0);
+    assert_eq!(level(735 * 15 - 1), 0.0);
+
+    // gating the channel again restarts the envelope
+    psg.write(3, 0x82);
+    psg.clock(cycles(1));
+    assert_eq!(psg.take_samples()[0].abs() * 60.0, 15.0);
+}
+
+#[test]
+fn silent_without_the_gate() {
+    let mut psg = square(441, 0x0f);
+    psg.write(3, 0x02);
+    psg.clock(cycles(100));
+    assert!(psg.take_samples().iter().all(|sample| *sample == 0.0));
+}
+
+#[test]
+fn wav_header_and_length() {
+    let mut wav = Vec::new();
+    write_wav(&mut wav, &[1.0, -1.0, 0.0, 2.0]).unwrap();
+
+    let u16_at = |i: usize| u16::from_le_bytes([wav[i], wav[i + 1]]);
+    let u32_at = |i: usize| u32::from_le_bytes([wav[i], wav[i + 1], wav[i + 2], wav[i + 3]]);
+
+    assert_eq!(wav.len(), 44 + 8);
+    assert_eq!(&wav[0..4], b"RIFF");
+    assert_eq!(u32_at(4), 36 + 8);
+    assert_eq!(&wav[8..16], b"WAVEfmt ");
+    assert_eq!((u32_at(16), u16_at(20), u16_at(22)), (16, 1, 1));
+    assert_eq!((u32_at(24), u32_at(28)), (SAMPLE_RATE, SAMPLE_RATE * 2));
+    assert_eq!((u16_at(32), u16_at(34)), (2, 16));
+    assert_eq!(&wav[36..40], b"data");
+    assert_eq!(u32_at(40), 8);
+
+    // out of range samples are clamped
+    let data = (0..4).map(|i| u16_at(44 + i * 2) as i16).collect::<Vec<_>>();
+    assert_eq!(data, [i16::MAX, -i16::MAX, 0, i16::MAX]);
+}
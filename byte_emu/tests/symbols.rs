@@ -0,0 +1,48 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+use byte_emu::emu::{parse_addr, symbols::Symbols};
+
+#[test]
+fn every_symbol_format() {
+    let symbols = Symbols::parse("
+        reset = $8000
+        nmi EQU 0x8010
+        $8020 irq
+        al C:8030 .vblank
+        8040 main ; a comment
+    ");
+
+    assert_eq!(symbols.addr("reset"), Some(0x8000));
+    assert_eq!(symbols.addr("nmi"), Some(0x8010));
+    assert_eq!(symbols.addr("irq"), Some(0x8020));
+    assert_eq!(symbols.addr("vblank"), Some(0x8030));
+    assert_eq!(symbols.addr("main"), Some(0x8040));
+    assert_eq!(symbols.label(0x8040), Some("main"));
+}
+
+#[test]
+fn addresses_that_look_like_labels() {
+    let symbols = Symbols::parse("C000 reset\nbeef loop\nstart C010");
+
+    assert_eq!(symbols.addr("reset"), Some(0xc000));
+    assert_eq!(symbols.addr("loop"), Some(0xbeef));
+    assert_eq!(symbols.addr("start"), Some(0xc010));
+    assert_eq!(symbols.addr("C000"), None);
+}
+
+#[test]
+fn lines_without_an_address_are_skipped() {
+    let symbols = Symbols::parse("reset\nreset main\n$8000\n= $8000");
+
+    assert_eq!(symbols.addr("reset"), None);
+    assert_eq!(symbols.addr("main"), None);
+}
+
+#[test]
+fn addresses() {
+    assert_eq!(parse_addr("$c000"), Some(0xc000));
+    assert_eq!(parse_addr("0xC000"), Some(0xc000));
+    assert_eq!(parse_addr(" 7f "), Some(0x7f));
+    assert_eq!(parse_addr("10000"), None);
+    assert_eq!(parse_addr("reset"), None);
+}
@@ -0,0 +1,91 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+use byte_emu::emu::{
+    core::{ByteEmu, ByteInputState},
+    video::DEFAULT_PALETTE,
+};
+
+fn program(main: &[u8]) -> Vec<u8> {
+    let mut program = vec![0; 0x10000];
+    program[0x8000..][..main.len()].copy_from_slice(main);
+    program[0xfffc..0xfffe].copy_from_slice(&[0x00, 0x80]);
+    program
+}
+
+#[test]
+fn the_terminal_port_collects_writes() {
+    let program = program(&[
+        0x78,             // SEI
+        0xa9, b'h',       // LDA #'h'
+        0x8d, 0x60, 0x7f, // STA $7f60
+        0xa9, b'i',       // LDA #'i'
+        0x8d, 0x60, 0x7f, // STA $7f60
+        0xad, 0x60, 0x7f, // LDA $7f60
+        0x85, 0x10,       // STA $10
+        0x4c, 0x10, 0x80, // JMP $8010
+    ]);
+
+    let mut emu = ByteEmu::default();
+    emu.load_program(&program, 0x0000);
+    emu.step([ByteInputState::empty(); 2]);
+
+    assert_eq!(emu.take_output(), b"hi");
+    assert!(emu.take_output().is_empty());
+    // reading the port returns 0
+    assert_eq!(emu.peek(0x10), 0);
+
+    // loading a program drops what the last one printed
+    emu.poke(0x7f60, b'!');
+    emu.load_program(&program, 0x0000);
+    assert!(emu.take_output().is_empty());
+}
+
+#[test]
+fn text_mode_draws_glyphs() {
+    let mut program = program(&[0x78, 0x4c, 0x01, 0x80]);
+    program[0x7f10] = 3;
+    program[0x7f11] = 0x20;
+    program[0x2000] = b'A';
+    // the high bit draws it in inverse
+    program[0x2001] = b'A' | 0x80;
+    // the second row of characters
+    program[0x2010] = b'a';
+
+    let mut emu = ByteEmu::default();
+    emu.load_program(&program, 0x0000);
+    emu.step([ByteInputState::empty(); 2]);
+
+    let frame = emu.framebuffer();
+    assert_eq!((frame.width, frame.height), (64, 64));
+    let rows: Vec<String> = frame.pixels
+        .chunks_exact(frame.width)
+        .take(8)
+        .map(|row| row[..8].iter().map(|&color| if color == DEFAULT_PALETTE[1] { '#' } else { '.' }).collect())
+        .collect();
+
+    assert_eq!(rows, [
+        ".#..#.##",
+        "#.#..#.#",
+        "###....#",
+        "#.#..#.#",
+        ".#......",
+        "#.#.....",
+        "###.....",
+        "#.#.....",
+    ]);
+}
+
+#[test]
+fn writes_go_through_the_devices() {
+    let mut emu = ByteEmu::default();
+    emu.load_program(&program(&[0x78, 0x4c, 0x01, 0x80]), 0x0000);
+
+    emu.write(0x7f60, b'x');
+    assert_eq!(emu.take_output(), b"x");
+
+    // only poking can change the rom
+    emu.write(0x8000, 0xea);
+    assert_eq!(emu.peek(0x8000), 0x78);
+    emu.poke(0x8000, 0xea);
+    assert_eq!(emu.peek(0x8000), 0xea);
+}
@@ -0,0 +1,303 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+use byte_emu::emu::{
+    core::{ByteEmu, ByteInputState, CLOCK_RATE},
+    video::DEFAULT_PALETTE,
+};
+
+const MODE: usize = 0x7f10;
+const TILE_MAP: usize = 0x7f11;
+const PATTERNS: usize = 0x7f12;
+const SPRITES: usize = 0x7f15;
+
+const MODE_TILES: u8 = 1;
+
+const SPRITE_ENABLED: u8 = 0b1000_0000;
+const SPRITE_BEHIND: u8 = 0b0000_0100;
+const SPRITE_FLIP_X: u8 = 0b0000_0001;
+
+// a 64x64 tile screen with the map at $2000, the patterns at $3000 and the
+// sprites at $3400. the program sits in SEI; JMP $8001.
+fn tiles() -> Vec<u8> {
+    let mut program = vec![0; 0x10000];
+    program[0x8000..0x8004].copy_from_slice(&[0x78, 0x4c, 0x01, 0x80]);
+    program[0xfffc..0xfffe].copy_from_slice(&[0x00, 0x80]);
+    program[MODE] = MODE_TILES;
+    program[TILE_MAP] = 0x20;
+    program[PATTERNS] = 0x30;
+    program[SPRITES] = 0x34;
+    program
+}
+
+// fills a tile with a single colour
+fn fill_tile(program: &mut [u8], tile: usize, color: u8) {
+    program[0x3000 + tile * 32..][..32].fill(color << 4 | color);
+}
+
+// colour 0 on the left half of every row, `color` on the right
+fn half_tile(program: &mut [u8], tile: usize, color: u8) {
+    for row in program[0x3000 + tile * 32..][..32].chunks_exact_mut(4) {
+        row.copy_from_slice(&[0x00, 0x00, color << 4 | color, color << 4 | color]);
+    }
+}
+
+fn sprite(program: &mut [u8], i: usize, x: u8, y: u8, tile: u8, flags: u8) {
+    program[0x3400 + i * 4..][..4].copy_from_slice(&[x, y, tile, flags]);
+}
+
+// runs a frame and returns the palette index of every pixel on the screen
+fn render(program: &[u8]) -> Vec<Vec<usize>> {
+    let mut emu = ByteEmu::default();
+    emu.load_program(program, 0x0000);
+    emu.step([ByteInputState::empty(); 2]);
+
+    let frame = emu.framebuffer();
+    assert_eq!((frame.width, frame.height), (64, 64));
+    frame.pixels
+        .chunks_exact(frame.width)
+        .map(|line| line.iter().map(|color| DEFAULT_PALETTE.iter().position(|c| c == color).unwrap()).collect())
+        .collect()
+}
+
+#[test]
+fn tiles_come_from_the_map() {
+    let mut program = tiles();
+    fill_tile(&mut program, 1, 5);
+    // two pixels to a byte, the left one in the high nibble
+    program[0x3000 + 2 * 32] = 0x9a;
+    program[0x2000 + 1] = 1;
+    program[0x2000 + 8 + 2] = 2;
+
+    let screen = render(&program);
+    assert_eq!(screen[0][7..17], [0, 5, 5, 5, 5, 5, 5, 5, 5, 0]);
+    assert_eq!(screen[7][8], 5);
+    assert_eq!(screen[8][8], 0);
+    assert_eq!(screen[8][16..19], [9, 10, 0]);
+}
+
+#[test]
+fn tiles_scroll() {
+    let mut program = tiles();
+    fill_tile(&mut program, 1, 5);
+    program[0x2000 + 9] = 1;
+    program[0x7f13] = 3;
+    program[0x7f14] = 8;
+
+    let screen = render(&program);
+    assert_eq!(screen[0][4..14], [0, 5, 5, 5, 5, 5, 5, 5, 5, 0]);
+    assert_eq!(screen[8][5], 0);
+}
+
+#[test]
+fn sprites_are_drawn_over_the_tiles() {
+    let mut program = tiles();
+    fill_tile(&mut program, 2, 7);
+    sprite(&mut program, 0, 20, 10, 2, SPRITE_ENABLED);
+    // disabled sprites aren't drawn
+    sprite(&mut program, 1, 40, 10, 2, 0);
+
+    let screen = render(&program);
+    assert_eq!(screen[9][20], 0);
+    assert_eq!(screen[10][19..29], [0, 7, 7, 7, 7, 7, 7, 7, 7, 0]);
+    assert_eq!(screen[17][27], 7);
+    assert_eq!(screen[18][27], 0);
+    assert!(screen[10][40..48].iter().all(|&color| color == 0));
+}
+
+#[test]
+fn colour_0_of_a_sprite_is_transparent() {
+    let mut program = tiles();
+    fill_tile(&mut program, 1, 5);
+    half_tile(&mut program, 2, 7);
+    program[0x2000..0x2040].fill(1);
+    sprite(&mut program, 0, 16, 16, 2, SPRITE_ENABLED);
+
+    let screen = render(&program);
+    assert_eq!(screen[16][16..24], [5, 5, 5, 5, 7, 7, 7, 7]);
+}
+
+#[test]
+fn lower_sprites_are_on_top() {
+    let mut program = tiles();
+    half_tile(&mut program, 2, 7);
+    fill_tile(&mut program, 3, 9);
+    sprite(&mut program, 0, 16, 16, 2, SPRITE_ENABLED);
+    sprite(&mut program, 1, 16, 16, 3, SPRITE_ENABLED);
+    sprite(&mut program, 2, 32, 16, 3, SPRITE_ENABLED);
+    sprite(&mut program, 3, 28, 16, 2, SPRITE_ENABLED);
+
+    let screen = render(&program);
+    // the higher sprite shows through the transparent pixels
+    assert_eq!(screen[16][16..24], [9, 9, 9, 9, 7, 7, 7, 7]);
+    assert_eq!(screen[16][28..40], [0, 0, 0, 0, 9, 9, 9, 9, 9, 9, 9, 9]);
+}
+
+#[test]
+fn sprites_behind_the_tiles() {
+    let mut program = tiles();
+    fill_tile(&mut program, 1, 5);
+    fill_tile(&mut program, 2, 7);
+    program[0x2000..0x2002].fill(1);
+    sprite(&mut program, 0, 12, 0, 2, SPRITE_ENABLED | SPRITE_BEHIND);
+
+    // only colour 0 of the tiles lets the sprite through
+    let screen = render(&program);
+    assert_eq!(screen[0][10..22], [5, 5, 5, 5, 5, 5, 7, 7, 7, 7, 0, 0]);
+}
+
+#[test]
+fn sprites_flip() {
+    let mut program = tiles();
+    half_tile(&mut program, 2, 7);
+    sprite(&mut program, 0, 0, 0, 2, SPRITE_ENABLED | SPRITE_FLIP_X);
+
+    let screen = render(&program);
+    assert_eq!(screen[0][0..8], [7, 7, 7, 7, 0, 0, 0, 0]);
+}
+
+// an irq handler at $9000 that keeps `LINE` and `STATUS` at $20 and $30
+// onwards, counting the interrupts at $12
+fn with_irq_handler(main: &[u8]) -> Vec<u8> {
+    let mut program = vec![0; 0x10000];
+    program[0x8000..][..main.len()].copy_from_slice(main);
+    program[0x9000..0x900f].copy_from_slice(&[
+        0xa6, 0x12,       // LDX $12
+        0xad, 0x17, 0x7f, // LDA LINE
+        0x95, 0x20,       // STA $20,X
+        0xad, 0x1a, 0x7f, // LDA STATUS
+        0x95, 0x30,       // STA $30,X
+        0xe6, 0x12,       // INC $12
+        0x40,             // RTI
+    ]);
+    program[0xfffc..0xfffe].copy_from_slice(&[0x00, 0x80]);
+    program[0xfffe..0x10000].copy_from_slice(&[0x00, 0x90]);
+    program
+}
+
+#[test]
+fn interrupts_at_the_compare_line_and_the_vertical_blank() {
+    // CLI; JMP $8001
+    let mut program = with_irq_handler(&[0x58, 0x4c, 0x01, 0x80]);
+    program[0x7f18] = 40;
+    program[0x7f19] = 1;
+
+    let mut emu = ByteEmu::default();
+    emu.load_program(&program, 0x0000);
+    emu.step([ByteInputState::empty(); 2]);
+
+    assert_eq!(emu.peek(0x12), 2);
+    assert_eq!([emu.peek(0x20), emu.peek(0x21)], [40, 128]);
+    // the line bit stays set until the program clears it
+    assert_eq!([emu.peek(0x30), emu.peek(0x31)], [0x40, 0xc0]);
+    // the next frame has started
+    assert_eq!([emu.peek(0x7f17), emu.peek(0x7f1a)], [0, 0x40]);
+
+    emu.poke(0x7f1a, 0);
+    emu.step([ByteInputState::empty(); 2]);
+    assert_eq!(emu.peek(0x12), 4);
+    assert_eq!([emu.peek(0x22), emu.peek(0x23)], [40, 128]);
+    assert_eq!([emu.peek(0x32), emu.peek(0x33)], [0x40, 0xc0]);
+}
+
+#[test]
+fn the_vertical_blank_interrupt_waits_for_cli() {
+    let program = with_irq_handler(&[
+        0x78,             // SEI
+        0xad, 0x17, 0x7f, // LDA LINE
+        0xc9, 150,        // CMP #150
+        0xd0, 0xf9,       // BNE $8001
+        0x58,             // CLI
+        0x4c, 0x09, 0x80, // JMP $8009
+    ]);
+
+    let mut emu = ByteEmu::default();
+    emu.load_program(&program, 0x0000);
+    emu.step([ByteInputState::empty(); 2]);
+
+    assert_eq!(emu.peek(0x12), 1);
+    assert_eq!([emu.peek(0x20), emu.peek(0x30)], [150, 0x80]);
+}
+
+#[test]
+fn sixty_frames_take_a_second() {
+    let mut emu = ByteEmu::default();
+    emu.load_program(&tiles(), 0x0000);
+    emu.step([ByteInputState::empty(); 2]);
+
+    let start = emu.cycle();
+    (0..60).for_each(|_| { emu.step([ByteInputState::empty(); 2]); });
+    // give or take the instruction that ends the last frame
+    let cycles = emu.cycle() - start;
+    assert!(cycles.abs_diff(CLOCK_RATE) < 8, "{cycles}");
+}
+
+// a bitmap screen at $2000 filled with colour 1, running `main`
+fn bitmap(main: &[u8]) -> Vec<u8> {
+    let mut program = vec![0; 0x10000];
+    program[0x8000..][..main.len()].copy_from_slice(main);
+    program[0xfffc..0xfffe].copy_from_slice(&[0x00, 0x80]);
+    program[0x00fd] = 0x02;
+    program[0x2000..0x6000].fill(1);
+    program
+}
+
+#[test]
+fn the_palette_can_be_changed_between_lines() {
+    let program = bitmap(&[
+        0x78,             // SEI
+        0xad, 0x17, 0x7f, // LDA LINE
+        0xc9, 64,         // CMP #64
+        0xd0, 0xf9,       // BNE $8001
+        0xa9, 0x12,       // LDA #$12
+        0x8d, 0x23, 0x7f, // STA $7f23
+        0x8d, 0x24, 0x7f, // STA $7f24
+        0x8d, 0x25, 0x7f, // STA $7f25
+        0x4c, 0x13, 0x80, // JMP $8013
+    ]);
+
+    let mut emu = ByteEmu::default();
+    emu.load_program(&program, 0x0000);
+    emu.step([ByteInputState::empty(); 2]);
+
+    // line 64 is the first line of row 32
+    let frame = emu.framebuffer();
+    let rows: Vec<_> = frame.pixels.chunks_exact(frame.width).map(|row| row[0]).collect();
+    assert!(rows[..32].iter().all(|&color| color == DEFAULT_PALETTE[1]));
+    assert!(rows[32..].iter().all(|&color| color == 0x121212ff));
+}
+
+#[test]
+fn the_image_does_not_set_the_palette() {
+    let mut program = bitmap(&[0x78, 0x4c, 0x01, 0x80]);
+    program[0x7f23..0x7f26].fill(0x12);
+
+    let mut emu = ByteEmu::default();
+    emu.load_program(&program, 0x0000);
+    emu.step([ByteInputState::empty(); 2]);
+    assert_eq!(emu.framebuffer().pixels[0], DEFAULT_PALETTE[1]);
+
+    emu.poke(0x7f23, 0x12);
+    emu.step([ByteInputState::empty(); 2]);
+    assert_eq!(emu.framebuffer().pixels[0], 0x12ffffff);
+}
+
+#[test]
+fn resolutions() {
+    let mut program = bitmap(&[0x78, 0x4c, 0x01, 0x80]);
+    program[0x7f16] = 1;
+    program[0x5fff] = 2;
+
+    let mut emu = ByteEmu::default();
+    emu.load_program(&program, 0x0000);
+    emu.step([ByteInputState::empty(); 2]);
+
+    let frame = emu.framebuffer();
+    assert_eq!((frame.width, frame.height), (128, 128));
+    assert_eq!(frame.pixels[127 * 128 + 126..], [DEFAULT_PALETTE[1], DEFAULT_PALETTE[2]]);
+
+    emu.poke(0x7f16, 0);
+    emu.step([ByteInputState::empty(); 2]);
+    let frame = emu.framebuffer();
+    assert_eq!((frame.width, frame.height), (64, 64));
+    assert!(frame.pixels.iter().all(|&color| color == DEFAULT_PALETTE[1]));
+}
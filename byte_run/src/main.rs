@@ -0,0 +1,278 @@
+use byte_core::debugger::{Breakpoint, StopReason};
+use byte_emu::emu::{core::ByteEmu, movie::Movie, sound};
+use byte_run::script::InputScript;
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+const USAGE: &str = "usage: byte_run <program> [options]
+       byte_run --play <movie> [options]
+
+options:
+  --frames <n>        number of frames to run, 60 or the length of the movie by default
+  --seed <n>          seed of the random number generator, 0 by default
+  --break <addr>      stop before executing the instruction at <addr>
+  --break-on-brk      stop before executing a BRK instruction
+  --input <file>      buttons to press, see byte_run/README.md
+  --record <file>     save the run as a movie
+  --play <file>       replay a movie, which brings its own program, seed and input
+  --regs              print the registers after running
+  --dump <lo>:<hi>    print the memory in [lo:hi] after running
+  --png <file>        save the last frame as a png
+  --wav <file>        save the audio as a wav file";
+
+struct Options {
+    program: Option<String>,
+    frames: Option<usize>,
+    seed: Option<u32>,
+    breakpoints: Vec<u16>,
+    break_on_brk: bool,
+    input: Option<String>,
+    record: Option<String>,
+    play: Option<String>,
+    regs: bool,
+    dumps: Vec<(u16, u16)>,
+    png: Option<String>,
+    wav: Option<String>,
+}
+
+fn main() {
+    if let Err(err) = parse_args().and_then(run) {
+        eprintln!("byte_run: {err}");
+        std::process::exit(1);
+    }
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut args = std::env::args().skip(1);
+    let mut options = Options {
+        program: None,
+        frames: None,
+        seed: None,
+        breakpoints: Vec::new(),
+        break_on_brk: false,
+        input: None,
+        record: None,
+        play: None,
+        regs: false,
+        dumps: Vec::new(),
+        png: None,
+        wav: None,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("`{arg}` expects a value\n\n{USAGE}"))
+        };
+
+        match arg.as_str() {
+            "--frames" => {
+                let frames = value()?;
+                options.frames = Some(
+                    frames
+                        .parse()
+                        .map_err(|_| format!("invalid frame count `{frames}`"))?,
+                );
+            }
+            "--seed" => {
+                let seed = value()?;
+                options.seed = Some(seed.parse().map_err(|_| format!("invalid seed `{seed}`"))?);
+            }
+            "--break" => options.breakpoints.push(parse_addr(&value()?)?),
+            "--break-on-brk" => options.break_on_brk = true,
+            "--input" => options.input = Some(value()?),
+            "--record" => options.record = Some(value()?),
+            "--play" => options.play = Some(value()?),
+            "--regs" => options.regs = true,
+            "--dump" => options.dumps.push(parse_range(&value()?)?),
+            "--png" => options.png = Some(value()?),
+            "--wav" => options.wav = Some(value()?),
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`\n\n{USAGE}")),
+            _ if options.program.is_none() => options.program = Some(arg),
+            _ => return Err(format!("unexpected argument `{arg}`\n\n{USAGE}")),
+        }
+    }
+
+    // a movie brings everything it needs to replay the run
+    if options.play.is_some() {
+        let conflict = [
+            (options.program.is_some(), "a program"),
+            (options.seed.is_some(), "`--seed`"),
+            (options.input.is_some(), "`--input`"),
+            (options.record.is_some(), "`--record`"),
+        ];
+        if let Some((_, name)) = conflict.iter().find(|(given, _)| *given) {
+            return Err(format!("`--play` can't be combined with {name}"));
+        }
+    } else if options.program.is_none() {
+        return Err(USAGE.to_string());
+    }
+
+    Ok(options)
+}
+
+// `$8000`, `0x8000` and `8000` are all the same address
+fn parse_addr(addr: &str) -> Result<u16, String> {
+    byte_emu::emu::parse_addr(addr).ok_or_else(|| format!("invalid address `{addr}`"))
+}
+
+fn parse_range(range: &str) -> Result<(u16, u16), String> {
+    let (lo, hi) = range
+        .split_once(':')
+        .ok_or_else(|| format!("invalid range `{range}`, expected <lo>:<hi>"))?;
+
+    Ok((parse_addr(lo)?, parse_addr(hi)?))
+}
+
+fn read(path: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|err| format!("failed to read `{path}`: {err}"))
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let script = match &options.input {
+        Some(path) => InputScript::parse(
+            &std::fs::read_to_string(path)
+                .map_err(|err| format!("failed to read `{path}`: {err}"))?,
+        )?,
+        None => InputScript::default(),
+    };
+
+    let mut emu = ByteEmu::default();
+    let frames = match (&options.play, &options.program) {
+        (Some(path), _) => {
+            let movie = Movie::from_bytes(&read(path)?)
+                .map_err(|err| format!("failed to read `{path}`: {err}"))?;
+            let frames = movie.len();
+
+            emu.play_movie(movie);
+            options.frames.unwrap_or(frames)
+        }
+        (None, Some(path)) => {
+            emu.set_seed(options.seed.unwrap_or(0));
+            emu.load_program(&read(path)?, 0x0000);
+            if options.record.is_some() {
+                emu.record_movie();
+            }
+            options.frames.unwrap_or(60)
+        }
+        (None, None) => unreachable!(),
+    };
+
+    let debugger = emu.debugger();
+    debugger.set_break_on_brk(options.break_on_brk);
+    for addr in &options.breakpoints {
+        debugger.add_breakpoint(Breakpoint::new(*addr));
+    }
+
+    let mut frame = 0;
+    let mut stop_reason = None;
+    let mut samples = Vec::new();
+    // whether the program's output, if any, ended with a newline
+    let mut newline = true;
+    while frame < frames && stop_reason.is_none() {
+        stop_reason = emu.step(script.state(frame));
+        samples.extend(emu.take_samples());
+        frame += 1;
+
+        let output = emu.take_output();
+        if let Some(last) = output.last() {
+            newline = *last == b'\n';
+            let mut stdout = std::io::stdout();
+            stdout
+                .write_all(&output)
+                .and_then(|_| stdout.flush())
+                .map_err(|err| format!("failed to write the output: {err}"))?;
+        }
+    }
+
+    if !newline {
+        println!();
+    }
+    match stop_reason {
+        Some(reason) => println!("stopped in frame {}: {}", frame - 1, describe(reason)),
+        None => println!("ran {frame} frames"),
+    }
+
+    if options.regs {
+        print_registers(&mut emu);
+    }
+    for range in &options.dumps {
+        print_memory(&emu, *range);
+    }
+    if let Some(path) = &options.png {
+        save_png(&emu, path).map_err(|err| format!("failed to write `{path}`: {err}"))?;
+    }
+    if let Some((path, movie)) = options.record.as_ref().zip(emu.stop_movie()) {
+        std::fs::write(path, movie.to_bytes())
+            .map_err(|err| format!("failed to write `{path}`: {err}"))?;
+    }
+    if let Some(path) = &options.wav {
+        File::create(path)
+            .and_then(|file| sound::write_wav(BufWriter::new(file), &samples))
+            .map_err(|err| format!("failed to write `{path}`: {err}"))?;
+    }
+
+    Ok(())
+}
+
+fn describe(reason: StopReason) -> String {
+    match reason {
+        StopReason::Breakpoint(pc) => format!("breakpoint at ${pc:04x}"),
+        StopReason::Brk(pc) => format!("BRK at ${pc:04x}"),
+        StopReason::Watchpoint { addr, access, pc } => {
+            format!("{access:?} of ${addr:04x} at ${pc:04x}")
+        }
+        StopReason::WriteFault { addr, pc } => format!("write to rom at ${addr:04x} by ${pc:04x}"),
+        StopReason::Goal => "goal reached".to_string(),
+        StopReason::Limit => "instruction limit".to_string(),
+    }
+}
+
+fn print_registers(emu: &mut ByteEmu) {
+    let cycle = emu.cycle();
+    let reg = emu.registers();
+
+    println!(
+        "pc: ${:04x} a: ${:02x} x: ${:02x} y: ${:02x} sp: ${:02x} p: {:08b} cycles: {cycle}",
+        reg.pc,
+        reg.a,
+        reg.x,
+        reg.y,
+        reg.sp,
+        reg.p.bits()
+    );
+}
+
+fn print_memory(emu: &ByteEmu, (lo, hi): (u16, u16)) {
+    let memory = emu.memory();
+
+    for start in (lo as usize..=hi as usize).step_by(16) {
+        let end = (start + 15).min(hi as usize);
+        let bytes = memory[start..=end]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        println!("${start:04x}: {bytes}");
+    }
+}
+
+fn save_png(emu: &ByteEmu, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let frame = emu.framebuffer();
+    let data: Vec<u8> = frame
+        .pixels
+        .iter()
+        .flat_map(|pixel| pixel.to_be_bytes())
+        .collect();
+
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = png::Encoder::new(file, frame.width as u32, frame.height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&data)?;
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use byte_emu::emu::{
+    core::ByteInputState,
+    input::{BUTTONS, PLAYERS},
+};
+
+// buttons to hold down from a given frame on, one change per line. the
+// buttons of player 2 are optional and follow the ones of player 1:
+//
+//   ; comment
+//   0   -
+//   30  right+a
+//   45  -        left
+#[derive(Debug, Default)]
+pub struct InputScript {
+    changes: Vec<(usize, [ByteInputState; PLAYERS])>,
+}
+
+impl InputScript {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut changes = Vec::new();
+
+        for (n, line) in source.lines().enumerate() {
+            let line = line.split(';').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let error = |msg: &str| format!("input script line {}: {msg}", n + 1);
+            let mut parts = line.split_whitespace();
+            let frame = parts
+                .next()
+                .and_then(|frame| frame.parse::<usize>().ok())
+                .ok_or_else(|| error("expected a frame number"))?;
+            let buttons = parts.collect::<Vec<_>>();
+            if buttons.is_empty() {
+                return Err(error("expected the buttons to hold"));
+            }
+            if buttons.len() > PLAYERS {
+                return Err(error(&format!("expected at most {PLAYERS} players")));
+            }
+
+            let mut state = [ByteInputState::empty(); PLAYERS];
+            for (player, buttons) in state.iter_mut().zip(buttons) {
+                *player = parse_buttons(buttons).map_err(|msg| error(&msg))?;
+            }
+
+            changes.push((frame, state));
+        }
+
+        changes.sort_by_key(|(frame, _)| *frame);
+        Ok(Self { changes })
+    }
+
+    // the buttons held down during `frame`
+    pub fn state(&self, frame: usize) -> [ByteInputState; PLAYERS] {
+        self.changes
+            .iter()
+            .take_while(|(start, _)| *start <= frame)
+            .last()
+            .map(|(_, state)| *state)
+            .unwrap_or([ByteInputState::empty(); PLAYERS])
+    }
+}
+
+fn parse_buttons(buttons: &str) -> Result<ByteInputState, String> {
+    if buttons == "-" {
+        return Ok(ByteInputState::empty());
+    }
+
+    buttons
+        .split('+')
+        .try_fold(ByteInputState::empty(), |state, name| {
+            BUTTONS
+                .iter()
+                .find(|(button, _)| button.eq_ignore_ascii_case(name))
+                .map(|(_, flag)| state | *flag)
+                .ok_or_else(|| format!("unknown button `{name}`"))
+        })
+}
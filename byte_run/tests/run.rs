@@ -0,0 +1,67 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+use byte_emu::emu::video::DEFAULT_PALETTE;
+use std::{fs::File, io::BufReader, path::PathBuf, process::Command};
+
+// a scratch file that doesn't collide with the other tests
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("byte_run-{}-{name}", std::process::id()))
+}
+
+// fills a 64x64 bitmap at $2000 with colour 2, sets a few registers and
+// stores $2a at $10
+fn program() -> Vec<u8> {
+    let mut program = vec![0; 0x10000];
+    program[0x8000..0x800c].copy_from_slice(&[
+        0x78,             // SEI
+        0xa9, 0x2a,       // LDA #$2a
+        0x85, 0x10,       // STA $10
+        0xa2, 0x07,       // LDX #$07
+        0xa0, 0x03,       // LDY #$03
+        0x4c, 0x09, 0x80, // JMP $8009
+    ]);
+    program[0xfffc..0xfffe].copy_from_slice(&[0x00, 0x80]);
+    program[0x00fd] = 0x02;
+    program[0x2000..0x3000].fill(2);
+    program
+}
+
+#[test]
+fn registers_memory_and_the_last_frame() {
+    let (bin, png) = (temp_path("program.bin"), temp_path("frame.png"));
+    std::fs::write(&bin, program()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_byte_run"))
+        .arg(&bin)
+        .args(["--frames", "2", "--regs", "--dump", "0010:0011", "--png"])
+        .arg(&png)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<_> = stdout.lines().collect();
+    assert_eq!(lines[0], "ran 2 frames");
+    assert!(lines[1].starts_with("pc: $8009 a: $2a x: $07 y: $03 "), "{}", lines[1]);
+    assert_eq!(lines[2], "$0010: 2a 00");
+
+    let mut decoder = png::Decoder::new(BufReader::new(File::open(&png).unwrap())).read_info().unwrap();
+    let mut data = vec![0; decoder.output_buffer_size().unwrap()];
+    let info = decoder.next_frame(&mut data).unwrap();
+    assert_eq!((info.width, info.height, info.color_type), (64, 64, png::ColorType::Rgba));
+    assert!(data.chunks_exact(4).all(|pixel| pixel == DEFAULT_PALETTE[2].to_be_bytes()));
+
+    std::fs::remove_file(bin).ok();
+    std::fs::remove_file(png).ok();
+}
+
+#[test]
+fn bad_arguments_fail() {
+    let output = Command::new(env!("CARGO_BIN_EXE_byte_run"))
+        .args(["program.bin", "--break", "nowhere"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "byte_run: invalid address `nowhere`\n");
+}
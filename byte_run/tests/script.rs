@@ -0,0 +1,48 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+use byte_emu::emu::core::ByteInputState;
+use byte_run::script::InputScript;
+
+const NONE: ByteInputState = ByteInputState::empty();
+
+#[test]
+fn buttons_are_held_until_the_next_change() {
+    let script = InputScript::parse("
+        ; frame  buttons
+        10       right+a
+        20       -
+    ").unwrap();
+
+    assert_eq!(script.state(0), [NONE, NONE]);
+    assert_eq!(script.state(9), [NONE, NONE]);
+    assert_eq!(script.state(10), [ByteInputState::RIGHT | ByteInputState::A, NONE]);
+    assert_eq!(script.state(19), [ByteInputState::RIGHT | ByteInputState::A, NONE]);
+    assert_eq!(script.state(20), [NONE, NONE]);
+    assert_eq!(script.state(1000), [NONE, NONE]);
+}
+
+#[test]
+fn player_2_is_optional() {
+    let script = InputScript::parse("0 up left+B ; both players\n5 start").unwrap();
+
+    assert_eq!(script.state(0), [ByteInputState::UP, ByteInputState::LEFT | ByteInputState::B]);
+    assert_eq!(script.state(5), [ByteInputState::START, NONE]);
+}
+
+#[test]
+fn lines_can_be_in_any_order() {
+    let script = InputScript::parse("30 select\n0 down").unwrap();
+
+    assert_eq!(script.state(29), [ByteInputState::DOWN, NONE]);
+    assert_eq!(script.state(30), [ByteInputState::SELECT, NONE]);
+}
+
+#[test]
+fn errors_name_the_line() {
+    let error = |source| InputScript::parse(source).unwrap_err();
+
+    assert_eq!(error("0 a\nright"), "input script line 2: expected a frame number");
+    assert_eq!(error("\n\n10"), "input script line 3: expected the buttons to hold");
+    assert_eq!(error("0 a b c"), "input script line 1: expected at most 2 players");
+    assert_eq!(error("0 a+jump"), "input script line 1: unknown button `jump`");
+}
@@ -0,0 +1,171 @@
+use byte_core::{cpu::Registers, debugger::StopReason};
+use byte_emu::emu::{core::ByteEmu, symbols::Symbols};
+
+// runaway subroutines are stopped after this many instructions
+const MAX_INSTRUCTIONS: usize = 1_000_000;
+
+// an address, either given directly or as a label from the symbol file
+pub trait Location {
+    fn resolve(&self, symbols: &Symbols) -> u16;
+}
+
+impl Location for u16 {
+    fn resolve(&self, _symbols: &Symbols) -> u16 {
+        *self
+    }
+}
+
+impl Location for &str {
+    fn resolve(&self, symbols: &Symbols) -> u16 {
+        symbols
+            .addr(self)
+            .unwrap_or_else(|| panic!("unknown label `{self}`"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Call {
+    // from the first instruction of the subroutine up to and including
+    // its `RTS`, the `JSR` that would normally call it isn't counted
+    pub cycles: u64,
+    pub instructions: usize,
+}
+
+#[derive(Debug)]
+pub enum CallError {
+    // the subroutine didn't return within the instruction limit
+    Limit(usize),
+    // the debugger stopped the execution
+    Stopped(StopReason),
+    Cpu(byte_core::Error),
+}
+
+impl std::fmt::Display for CallError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CallError::Limit(n) => write!(f, "the subroutine didn't return after {n} instructions"),
+            CallError::Stopped(reason) => write!(f, "stopped: {reason:x?}"),
+            CallError::Cpu(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for CallError {}
+
+// a console to run subroutines of a program on, one at a time and without
+// the frame loop getting in the way:
+//
+//   let mut machine = Machine::new(PROGRAM).with_symbols(SYMBOLS);
+//   machine.poke("POS_L", 0x00);
+//   let call = machine.call("move_left").unwrap();
+//   assert_eq!(machine.peek("POS_L"), 0xff);
+pub struct Machine {
+    emu: ByteEmu,
+    symbols: Symbols,
+    max_instructions: usize,
+}
+
+impl Machine {
+    // loads a 64 KiB image the same way `byte_emu` does
+    pub fn new(program: &[u8]) -> Self {
+        let mut emu = ByteEmu::default();
+        emu.load_program(program, 0x0000);
+
+        Self {
+            emu,
+            symbols: Symbols::default(),
+            max_instructions: MAX_INSTRUCTIONS,
+        }
+    }
+
+    pub fn with_symbols(mut self, source: &str) -> Self {
+        self.symbols = Symbols::parse(source);
+        self
+    }
+
+    pub fn with_max_instructions(mut self, max_instructions: usize) -> Self {
+        self.max_instructions = max_instructions;
+        self
+    }
+
+    pub fn addr(&self, location: impl Location) -> u16 {
+        location.resolve(&self.symbols)
+    }
+
+    pub fn reg(&mut self) -> &mut Registers {
+        self.emu.registers()
+    }
+
+    pub fn cycle(&self) -> u64 {
+        self.emu.cycle()
+    }
+
+    pub fn peek(&self, location: impl Location) -> u8 {
+        self.emu.peek(self.addr(location))
+    }
+
+    pub fn peek_u16(&self, location: impl Location) -> u16 {
+        let addr = self.addr(location);
+        u16::from_le_bytes([self.emu.peek(addr), self.emu.peek(addr.wrapping_add(1))])
+    }
+
+    pub fn poke(&mut self, location: impl Location, byte: u8) {
+        let addr = self.addr(location);
+        self.emu.poke(addr, byte);
+    }
+
+    pub fn poke_u16(&mut self, location: impl Location, word: u16) {
+        self.load(location, &word.to_le_bytes());
+    }
+
+    pub fn load(&mut self, location: impl Location, bytes: &[u8]) {
+        let addr = self.addr(location);
+
+        for (i, byte) in bytes.iter().enumerate() {
+            self.emu.poke(addr.wrapping_add(i as u16), *byte);
+        }
+    }
+
+    pub fn emu(&mut self) -> &mut ByteEmu {
+        &mut self.emu
+    }
+
+    // runs the subroutine at `location` until it returns. a return address
+    // is pushed the way `JSR` would, pointing back at the current pc.
+    pub fn call(&mut self, location: impl Location) -> Result<Call, CallError> {
+        let target = self.addr(location);
+        let start = self.emu.cycle();
+        let (ret, sp) = {
+            let reg = self.emu.registers();
+            (reg.pc, reg.sp)
+        };
+
+        for byte in ret.wrapping_sub(1).to_be_bytes() {
+            let sp = self.emu.registers().sp;
+            self.emu.poke(0x0100 + sp as u16, byte);
+            self.emu.registers().sp = sp.wrapping_sub(1);
+        }
+        self.emu.registers().pc = target;
+
+        for instructions in 0..=self.max_instructions {
+            let reg = self.emu.registers();
+            if reg.pc == ret && reg.sp == sp {
+                return Ok(Call {
+                    cycles: self.emu.cycle() - start,
+                    instructions,
+                });
+            }
+            if instructions == self.max_instructions {
+                break;
+            }
+
+            match self.emu.step_instruction() {
+                Ok(None) => (),
+                Ok(Some(reason)) => return Err(CallError::Stopped(reason)),
+                Err(err) => return Err(CallError::Cpu(err)),
+            }
+        }
+
+        Err(CallError::Limit(self.max_instructions))
+    }
+}
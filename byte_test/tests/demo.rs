@@ -0,0 +1,58 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+use byte_test::{CallError, Machine};
+
+const PROGRAM: &[u8] = include_bytes!("../../byte_emu/assets/demo.bin");
+const SYMBOLS: &str = include_str!("../../byte_emu/assets/demo.sym");
+
+fn init() -> Machine {
+    Machine::new(PROGRAM).with_symbols(SYMBOLS)
+}
+
+#[test]
+fn move_left() {
+    let mut machine = init();
+    machine.poke_u16("POS_L", 0x1701);
+
+    let call = machine.call("move_left").unwrap();
+    assert_eq!(machine.peek_u16("POS_L"), 0x1700);
+    assert_eq!((call.instructions, call.cycles), (6, 19));
+}
+
+#[test]
+fn move_left_borrows_from_the_high_byte() {
+    let mut machine = init();
+    machine.poke_u16("POS_L", 0x1700);
+
+    let call = machine.call("move_left").unwrap();
+    assert_eq!(machine.peek_u16("POS_L"), 0x16ff);
+    assert_eq!((call.instructions, call.cycles), (7, 23));
+}
+
+#[test]
+fn calls_nest() {
+    let mut machine = init();
+    machine.poke_u16("POS_L", 0x1740);
+    machine.poke("INPUT", 0b00001000);
+
+    let pc = machine.reg().pc;
+    let sp = machine.reg().sp;
+    machine.call("handle_input").unwrap();
+
+    assert_eq!(machine.peek_u16("POS_L"), 0x1700);
+    assert_eq!(machine.peek("PREV_KEY"), 0b00001000);
+    assert_eq!((machine.reg().pc, machine.reg().sp), (pc, sp));
+}
+
+#[test]
+fn runaway_subroutine() {
+    let mut machine = init().with_max_instructions(100);
+
+    assert!(matches!(machine.call("loop"), Err(CallError::Limit(100))));
+}
+
+#[test]
+#[should_panic(expected = "unknown label `nope`")]
+fn unknown_label() {
+    init().call("nope").ok();
+}